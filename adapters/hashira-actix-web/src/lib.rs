@@ -28,6 +28,11 @@ where
 {
     /// Starts the server.
     async fn serve(self, app: AppService) -> Result<(), hashira::error::BoxError> {
+        // Export the app as static files instead of starting the server
+        if let Some(export_dir) = hashira::env::get_export_dir() {
+            return hashira::server::export(&app, export_dir).await;
+        }
+
         let host = hashira::env::get_host().unwrap_or_else(|| String::from("127.0.0.1"));
         let port = hashira::env::get_port().unwrap_or(5000);
         let addr: SocketAddr = format!("{host}:{port}").as_str().parse().unwrap();
@@ -1,4 +1,8 @@
-use hashira::{actions::use_action_with_callback, components::ActionForm, page_component};
+use hashira::{
+    actions::{use_action_with_options, UseActionOptions},
+    components::ActionForm,
+    page_component,
+};
 use multer_derive::{FormFile, FromMultipart};
 use serde::{Deserialize, Serialize};
 use web_sys::window;
@@ -97,16 +101,18 @@ pub struct UploadPageProps {
 
 #[page_component("/", render = "render")]
 pub fn UploadsPage(props: &UploadPageProps) -> yew::Html {
-    let action = use_action_with_callback(move |ret| match ret {
-        Ok(_) => {
-            let window = window().unwrap();
-            window.location().reload().unwrap();
-        }
-        Err(_) => {
-            let window = window().unwrap();
-            window.alert_with_message("failed to upload file").unwrap();
-        }
-    });
+    let action = use_action_with_options(UseActionOptions::new().track_progress().on_complete(
+        move |ret| match &*ret {
+            Ok(_) => {
+                let window = window().unwrap();
+                window.location().reload().unwrap();
+            }
+            Err(_) => {
+                let window = window().unwrap();
+                window.alert_with_message("failed to upload file").unwrap();
+            }
+        },
+    ));
 
     yew::html! {
        <>
@@ -114,6 +120,12 @@ pub fn UploadsPage(props: &UploadPageProps) -> yew::Html {
                 <input type="file" name="image" accept="image/*" required={true} />
                 <button>{"Upload"}</button>
             </ActionForm<UploadFileAction>>
+            if let Some(progress) = action.upload_progress() {
+                <div class="upload-progress">
+                    <progress max="1" value={progress.fraction().unwrap_or_default().to_string()} />
+                    <button onclick={let action = action.clone(); move |_| action.cancel()}>{"Cancel"}</button>
+                </div>
+            }
             <div class="container">
                 if props.files.is_empty() {
                     <strong id="empty">{"No images, upload something..."}</strong>
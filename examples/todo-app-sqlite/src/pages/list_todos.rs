@@ -9,7 +9,7 @@ use hashira::{
     page_component,
     server::Metadata,
     utils::show_alert,
-    web::{Inject, Response},
+    web::{Inject, Json, Response},
 };
 use serde::{Deserialize, Serialize};
 use yew::{function_component, use_state, Callback, Properties};
@@ -89,7 +89,7 @@ pub fn ListTodosPage(props: &ListTodosPageProps) -> yew::Html {
 #[action("/api/todos/toggle")]
 #[cfg(feature = "client")]
 #[allow(dead_code)]
-pub async fn ToggleDoneAction() -> hashira::Result<()> {
+pub async fn ToggleDoneAction() -> hashira::Result<Json<bool>> {
     unreachable!()
 }
 
@@ -98,7 +98,7 @@ pub async fn ToggleDoneAction() -> hashira::Result<()> {
 pub async fn ToggleDoneAction(
     form: hashira::web::Form<ToggleTodo>,
     Inject(pool): Inject<sqlx::SqlitePool>,
-) -> hashira::Result<()> {
+) -> hashira::Result<Json<bool>> {
     use axum::http::StatusCode;
     use hashira::error::ResponseError;
 
@@ -125,7 +125,7 @@ pub async fn ToggleDoneAction(
     .execute(&mut conn)
     .await?;
 
-    Ok(())
+    Ok(Json(done))
 }
 
 #[action("/api/todos/delete")]
@@ -157,17 +157,15 @@ struct TodoItemProps {
 #[function_component]
 fn TodoItem(props: &TodoItemProps) -> yew::Html {
     let toggle_action = use_action::<ToggleDoneAction, _>();
-    let checked = use_state(|| props.todo.done);
+    let checked = toggle_action.data().copied().unwrap_or(props.todo.done);
     let id = props.todo.id;
     let on_delete = props.on_delete.clone();
     let toggle = {
         let toggle_action = toggle_action.clone();
-        let checked = checked.clone();
         move |_| {
-            checked.set(!*checked);
-
+            // The checkbox is updated before the server responds
             toggle_action
-                .send(hashira::web::Form(ToggleTodo { id }))
+                .send_optimistic(hashira::web::Form(ToggleTodo { id }), !checked)
                 .unwrap();
         }
     };
@@ -178,7 +176,7 @@ fn TodoItem(props: &TodoItemProps) -> yew::Html {
                 <h2 class="text-lg font-medium">{format!("{}", props.todo.title)}</h2>
                 <label class="flex items-center">
                 <span class="text-gray-600 mr-2">{"Done?"}</span>
-                <input type="checkbox" class="form-checkbox" onchange={toggle} checked={*checked}/>
+                <input type="checkbox" class="form-checkbox" onchange={toggle} checked={checked}/>
                 </label>
             </div>
             if let Some(description) = &props.todo.description {
@@ -0,0 +1,26 @@
+use super::BuildOptions;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportOptions {
+    #[command(flatten)]
+    pub build_opts: BuildOptions,
+
+    // ## Options above come from the `BuildOptions` ##
+    #[arg(
+        short,
+        long,
+        help = "The server path where the static files will be serve",
+        default_value = "/static"
+    )]
+    pub static_dir: String,
+
+    #[arg(
+        short,
+        long,
+        help = "Directory where the exported pages and static files will be written",
+        default_value = "out"
+    )]
+    pub out_dir: PathBuf,
+}
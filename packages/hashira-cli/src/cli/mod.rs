@@ -1,5 +1,6 @@
 mod build_options;
 mod dev_options;
+mod export_options;
 mod log_level;
 mod new_options;
 mod run_options;
@@ -7,6 +8,7 @@ mod wasm_opt_level;
 
 pub use build_options::*;
 pub use dev_options::*;
+pub use export_options::*;
 pub use log_level::*;
 pub use new_options::*;
 pub use run_options::*;
@@ -40,6 +42,9 @@ pub enum Commands {
 
     #[command(about = "Runs the project in watch mode")]
     Dev(DevOptions),
+
+    #[command(about = "Build the project and export the pages as static files")]
+    Export(ExportOptions),
 }
 
 #[cfg(test)]
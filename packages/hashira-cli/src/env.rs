@@ -17,4 +17,7 @@ pub const HASHIRA_LIVE_RELOAD_HOST: &str = "HASHIRA_LIVE_RELOAD_HOST";
 pub const HASHIRA_LIVE_RELOAD_PORT: &str = "HASHIRA_LIVE_RELOAD_PORT";
 
 /// Name of the environment variable with the name of the wasm library.
-pub const HASHIRA_WASM_LIB: &str = "HASHIRA_WASM_LIB";
\ No newline at end of file
+pub const HASHIRA_WASM_LIB: &str = "HASHIRA_WASM_LIB";
+
+/// Name of the environment variable with the directory where the app will be exported as static files.
+pub const HASHIRA_EXPORT_DIR: &str = "HASHIRA_EXPORT_DIR";
\ No newline at end of file
@@ -12,7 +12,7 @@ mod config;
 use anyhow::Context;
 use clap::Parser;
 use cli::{Cli, Commands, LogLevel};
-use tasks::{build::BuildTask, dev::DevTask, new::NewTask, run::RunTask};
+use tasks::{build::BuildTask, dev::DevTask, export::ExportTask, new::NewTask, run::RunTask};
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -25,6 +25,7 @@ async fn main() -> anyhow::Result<()> {
         Commands::Build(opts) => BuildTask::new(opts).run().await,
         Commands::Run(opts) => RunTask::new(opts).run().await,
         Commands::Dev(opts) => DevTask::new(opts).run().await,
+        Commands::Export(opts) => ExportTask::new(opts).run().await,
     }
 }
 
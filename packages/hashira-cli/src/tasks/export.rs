@@ -0,0 +1,119 @@
+use super::run::RunTask;
+use crate::cli::ExportOptions;
+use crate::emojis;
+use anyhow::Context;
+use std::{path::Path, sync::Arc};
+
+// File written in the export directory to know it can be safely cleared
+const EXPORT_MARKER_FILE: &str = ".hashira-export";
+
+pub struct ExportTask {
+    // Options used to export the project
+    options: ExportOptions,
+}
+
+impl ExportTask {
+    pub fn new(options: ExportOptions) -> Self {
+        ExportTask { options }
+    }
+
+    /// Builds the app and runs it in export mode, which writes the pages as static files.
+    pub async fn run(self) -> anyhow::Result<()> {
+        let opts = self.options;
+        let current_dir = std::env::current_dir()?;
+        let out_dir = current_dir.join(&opts.out_dir);
+        prepare_out_dir(&out_dir, &current_dir).await?;
+
+        let mut run_task = RunTask {
+            envs: Default::default(),
+            host: String::from("127.0.0.1"),
+            port: 0,
+            is_dev: false,
+            static_dir: opts.static_dir,
+            options: Arc::new(opts.build_opts),
+            build_done_signal: None,
+            interrupt_signal: None,
+        };
+
+        run_task.env(
+            crate::env::HASHIRA_EXPORT_DIR,
+            out_dir.to_string_lossy().into_owned(),
+        );
+
+        if !run_task.build().await? {
+            return Ok(());
+        }
+
+        run_task.exec().await?;
+        tracing::info!("{}Exported to: {}", emojis::DONE, out_dir.display());
+        Ok(())
+    }
+}
+
+// Clears the output directory, only if is empty or was created by a previous export
+async fn prepare_out_dir(out_dir: &Path, current_dir: &Path) -> anyhow::Result<()> {
+    if out_dir.exists() {
+        let out_dir = tokio::fs::canonicalize(out_dir).await?;
+        let current_dir = tokio::fs::canonicalize(current_dir).await?;
+
+        if current_dir.starts_with(&out_dir) {
+            anyhow::bail!(
+                "cannot export to `{}`, it contains the current directory",
+                out_dir.display()
+            );
+        }
+
+        let mut entries = tokio::fs::read_dir(&out_dir).await?;
+        let is_empty = entries.next_entry().await?.is_none();
+        let is_export = out_dir.join(EXPORT_MARKER_FILE).exists();
+
+        if !is_empty && !is_export {
+            anyhow::bail!(
+                "cannot export to `{}`, the directory is not empty and is not a previous export",
+                out_dir.display()
+            );
+        }
+
+        tokio::fs::remove_dir_all(&out_dir)
+            .await
+            .with_context(|| format!("failed to remove `{}`", out_dir.display()))?;
+    }
+
+    tokio::fs::create_dir_all(out_dir)
+        .await
+        .with_context(|| format!("failed to create `{}`", out_dir.display()))?;
+
+    tokio::fs::write(out_dir.join(EXPORT_MARKER_FILE), "").await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prepare_out_dir, EXPORT_MARKER_FILE};
+
+    #[tokio::test]
+    async fn prepare_out_dir_test() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("project");
+        let src = project.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("main.rs"), "fn main() {}").unwrap();
+
+        // The current directory and its ancestors are never cleared
+        assert!(prepare_out_dir(&project, &project).await.is_err());
+        assert!(prepare_out_dir(root.path(), &project).await.is_err());
+
+        // Non-empty directories that are not an export are never cleared
+        assert!(prepare_out_dir(&src, &project).await.is_err());
+        assert!(src.join("main.rs").exists());
+
+        // Previous exports are cleared
+        let out = project.join("out");
+        prepare_out_dir(&out, &project).await.unwrap();
+        std::fs::write(out.join("index.html"), "").unwrap();
+
+        prepare_out_dir(&out, &project).await.unwrap();
+        assert!(out.join(EXPORT_MARKER_FILE).exists());
+        assert!(!out.join("index.html").exists());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod build;
 pub mod dev;
+pub mod export;
 pub mod run;
 pub mod new;
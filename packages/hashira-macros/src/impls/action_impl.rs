@@ -1,22 +1,63 @@
 // #[action] <-- autogenerated route
 // #[action("/route")]
+// #[action("/route", csrf = false)]
+// #[action("/route", guard = path::to::guard)]
 
 use proc_macro2::TokenStream;
-use syn::{parse::Parse, spanned::Spanned, ItemFn, LitStr};
+use syn::{parse::Parse, spanned::Spanned, ItemFn, LitBool, LitStr, Token};
 
 #[derive(Clone)]
 pub struct ActionAttr {
     route: Option<String>,
+    csrf: Option<bool>,
+    guards: Vec<syn::Path>,
 }
 
 impl Parse for ActionAttr {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let route: Option<LitStr> = input
-            .parse()
-            .map_err(|_| syn::Error::new(input.span(), "server actions should declare a route"))?;
+        let route: Option<LitStr> = if input.peek(LitStr) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let mut csrf = None;
+        let mut guards = vec![];
+
+        if route.is_some() && !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+
+        while !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            match ident.to_string().as_str() {
+                "csrf" => {
+                    let value: LitBool = input.parse()?;
+                    csrf = Some(value.value());
+                }
+                "guard" => {
+                    let path: syn::Path = input.parse()?;
+                    guards.push(path);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown action option `{ident}`"),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
 
         Ok(ActionAttr {
             route: route.map(|x| x.value()),
+            csrf,
+            guards,
         })
     }
 }
@@ -51,6 +92,36 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
         }
     };
 
+    // The function to call the action from the client, e.g. `CreateTodo::fetch(input)`
+    let call_fn = call_action_fn(&item_fn, &name)?;
+
+    let csrf_protection = match attr.csrf {
+        Some(csrf) => quote::quote! {
+            fn csrf_protection() -> bool {
+                #csrf
+            }
+        },
+        None => TokenStream::new(),
+    };
+
+    // The guards are only called on the server
+    let guards = attr.guards;
+    let guard = if guards.is_empty() {
+        TokenStream::new()
+    } else {
+        quote::quote! {
+            #[cfg(not(feature = "client"))]
+            fn guard(ctx: ::hashira::app::RequestContext) -> ::hashira::types::BoxFuture<::std::result::Result<(), ::hashira::error::ServerError>> {
+                ::std::boxed::Box::pin(async move {
+                    #(
+                        ::hashira::actions::ActionGuard::check(&#guards, ctx.clone()).await?;
+                    )*
+                    ::std::result::Result::Ok(())
+                })
+            }
+        }
+    };
+
     // We rename the function to `_{name}`
     let mut new_item_fn = item_fn.clone();
     let new_item_fn_ident = syn::Ident::new(&format!("_{name}"), name.span());
@@ -64,6 +135,8 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
             _marker: ::std::marker::PhantomData<()>
         }
 
+        #call_fn
+
         const _: () = {
             #[allow(non_snake_case)]
             #[allow(non_camel_case_types)]
@@ -85,6 +158,10 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
                    #route
                 }
 
+                #csrf_protection
+
+                #guard
+
                 fn call(ctx: ::hashira::app::RequestContext, body: ::hashira::web::Body) -> ::hashira::types::BoxFuture<::hashira::Result<Self::Response>> {
                     let fut = ::hashira::actions::call_action(ctx, body, #new_item_fn_ident);
                     ::std::boxed::Box::pin(fut)
@@ -94,6 +171,93 @@ pub fn action_impl(attr: ActionAttr, item_fn: ItemFn) -> syn::Result<TokenStream
     })
 }
 
+// Returns the name of the extractor if the type is the input of the action.
+fn input_type_name(ty: &syn::Type) -> Option<String> {
+    const INPUT_TYPES: [&str; 5] = ["Form", "Json", "Query", "Valid", "Multipart"];
+
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|x| x.ident.to_string())
+            .filter(|name| INPUT_TYPES.contains(&name.as_str())),
+        _ => None,
+    }
+}
+
+fn call_action_fn(item_fn: &ItemFn, name: &syn::Ident) -> syn::Result<TokenStream> {
+    /*
+    We generate an associated function that sends the input of the action to the server:
+        #[action]
+        async fn CreateTodo(form: Form<NewTodo>, pool: Inject<MySqlPool>) -> Json<Todo> {
+            // logic
+        }
+
+    Generates:
+        impl CreateTodo {
+            async fn fetch(input: Form<NewTodo>) -> hashira::Result<Todo>
+            where
+                for<'__a> Form<NewTodo>: IntoRequestConfig
+            {
+                hashira::actions::fetch_action::<CreateTodo, _>(input, RequestOptions::new()).await
+            }
+        }
+
+    The `for<'__a>` bound is only checked when the function is called,
+    so inputs that cannot be serialized don't fail to compile.
+    */
+
+    let inputs = item_fn
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => {
+                input_type_name(&pat_type.ty).map(|type_name| (type_name, &pat_type.ty))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let input_ty = match inputs.as_slice() {
+        [] => quote::quote! { () },
+        // A multipart form is sent from the client using a `FormData`
+        [(type_name, _)] if type_name == "Multipart" => {
+            quote::quote! { ::hashira::actions::AnyForm }
+        }
+        [(_, ty)] => quote::quote! { #ty },
+        [_, (_, ty), ..] => {
+            return Err(syn::Error::new(
+                ty.span(),
+                "a server action can only take one input: `Form`, `Json`, `Query`, `Valid` or `Multipart`",
+            ));
+        }
+    };
+
+    let (params, input) = match inputs.as_slice() {
+        [] => (TokenStream::new(), quote::quote! { () }),
+        _ => (quote::quote! { input: #input_ty }, quote::quote! { input }),
+    };
+
+    let vis = &item_fn.vis;
+    let doc = format!("Calls the [`{name}`] server action and returns its data.");
+
+    Ok(quote::quote! {
+        #[automatically_derived]
+        impl #name {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #vis async fn fetch(#params) -> ::hashira::Result<<<#name as ::hashira::actions::Action>::Response as ::hashira::web::IntoJsonResponse>::Data>
+            where
+                for<'__a> #input_ty: ::hashira::actions::into_request_config::IntoRequestConfig,
+            {
+                ::hashira::actions::fetch_action::<#name, _>(#input, ::hashira::actions::RequestOptions::new()).await
+            }
+        }
+    })
+}
+
 fn placeholder_item_fn(item_fn: &ItemFn) -> ItemFn {
     /*
     We replace the body of the function and remove all the parameters:
@@ -2,4 +2,6 @@ mod page_component_impl;
 pub use page_component_impl::*;
 
 mod action_impl;
-pub use action_impl::*;
\ No newline at end of file
+pub use action_impl::*;
+mod validate_impl;
+pub use validate_impl::*;
@@ -1,14 +1,23 @@
+use hashira_route::RouteSegment;
 use proc_macro2::{Ident, Span, TokenStream};
 use syn::{parse::Parse, ItemFn, LitStr, Result};
 
 // #[page_component("/route")]
 // #[page_component("/route", render = "path::to::function")]
+// #[page_component("/route", loader = "path::to::function")]
+// #[page_component("/route", layout = "path::to::Layout")]
+// #[page_component("/route/:id", static_params = "path::to::function")]
+// #[page_component("/route", revalidate = 60)]
 // #[page_component(None, render = "path::to::function")]
 
 #[derive(Clone)]
 pub struct PageComponentAttr {
     route: Option<LitStr>,
     render: Option<Ident>,
+    loader: Option<syn::Path>,
+    layout: Option<syn::Path>,
+    static_params: Option<syn::Path>,
+    revalidate: Option<u64>,
 }
 
 impl Parse for PageComponentAttr {
@@ -32,38 +41,75 @@ impl Parse for PageComponentAttr {
             }
         };
 
-        let _comma: Option<syn::Token![,]> = input.parse()?;
+        let mut render = None;
+        let mut loader = None;
+        let mut layout = None;
+        let mut static_params = None;
+        let mut revalidate = None;
 
-        if _comma.is_none() {
-            if !input.is_empty() {
-                return Err(input.error(
-                    "expected #[page_component(\"/route\", render = \"path::to::render\")]",
-                ));
+        while !input.is_empty() {
+            let _comma: syn::Token![,] = input.parse().map_err(|_| {
+                input.error("expected #[page_component(\"/route\", render = \"path::to::render\")]")
+            })?;
+
+            // Trailing comma
+            if input.is_empty() {
+                break;
             }
 
-            return Ok(PageComponentAttr {
-                route,
-                render: None,
-            });
-        }
+            let ident_span = input.span();
+            let ident: Ident = input.parse()?;
+            let _equals: syn::Token![=] = input.parse()?;
+
+            // `revalidate` is the number of seconds, the other values are paths
+            if ident == "revalidate" {
+                if revalidate.is_some() {
+                    return Err(syn::Error::new(ident_span, "duplicated `revalidate`"));
+                }
 
-        let ident_span = input.span();
-        let ident: syn::Path = input.parse()?;
+                let secs: syn::LitInt = input.parse()?;
+                revalidate = Some(secs.base10_parse::<u64>()?);
+                continue;
+            }
+
+            let value: LitStr = input.parse()?;
 
-        if !ident.is_ident("render") {
-            return Err(syn::Error::new(
-                ident_span,
-                "invalid signature, expected: #[page_component(render = \"path::to::render\")]",
-            ));
+            match ident.to_string().as_str() {
+                "render" if render.is_none() => {
+                    render = Some(Ident::new(&value.value(), Span::call_site()));
+                }
+                "loader" if loader.is_none() => {
+                    loader = Some(value.parse::<syn::Path>()?);
+                }
+                "layout" if layout.is_none() => {
+                    layout = Some(value.parse::<syn::Path>()?);
+                }
+                "static_params" if static_params.is_none() => {
+                    static_params = Some(value.parse::<syn::Path>()?);
+                }
+                "render" | "loader" | "layout" | "static_params" => {
+                    return Err(syn::Error::new(ident_span, format!("duplicated `{ident}`")));
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident_span,
+                        "invalid signature, expected: #[page_component(\"/route\", loader = \"path::to::loader\", layout = \"path::to::Layout\")]",
+                    ));
+                }
+            }
         }
 
-        let _equals: syn::Token![=] = input.parse()?;
-        let render_str: LitStr = input.parse()?;
-        let render = Ident::new(&render_str.value(), Span::call_site());
+        if render.is_some() && loader.is_some() {
+            return Err(input.error("a page can only declare a `render` or a `loader` function, not both"));
+        }
 
         Ok(PageComponentAttr {
             route,
-            render: Some(render),
+            render,
+            loader,
+            layout,
+            static_params,
+            revalidate,
         })
     }
 }
@@ -74,19 +120,48 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
     let component = item_fn.sig.ident.clone();
     let name = component.to_string();
 
+    let url_fn = match &attr.route {
+        Some(lit_str) => {
+            let url_fn = page_url_fn(lit_str)?;
+            Some(quote::quote! {
+                #[automatically_derived]
+                impl #component {
+                    #url_fn
+                }
+            })
+        }
+        None => None,
+    };
+
     let route = {
         let lit_str = attr.route;
         quote::quote! { Some(#lit_str) }
     };
 
-    let render = match attr.render {
-        Some(render_fn) => {
+    let render = match (attr.render, attr.loader) {
+        (Some(render_fn), _) => {
             quote::quote! {
                 let fut = ::hashira::components::handler::call_render(ctx, body, #render_fn);
                 std::boxed::Box::pin(fut)
             }
         }
-        None => {
+        (None, Some(loader_fn)) => {
+            // The loader only exists on the server
+            quote::quote! {
+                #[cfg(feature = "client")]
+                {
+                    let _ = (ctx, body);
+                    std::unreachable!()
+                }
+
+                #[cfg(not(feature = "client"))]
+                {
+                    let fut = ::hashira::components::handler::call_loader::<Self, BASE, _, _, _>(ctx, body, #loader_fn);
+                    std::boxed::Box::pin(fut)
+                }
+            }
+        }
+        (None, None) => {
             // TODO: Use FutureExt::map
             quote::quote! {
                 std::boxed::Box::pin(async move {
@@ -97,6 +172,39 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
         }
     };
 
+    let layout = match attr.layout {
+        Some(layout) => quote::quote! {
+            Some(::hashira::components::PageLayout::of::<#layout>())
+        },
+        None => quote::quote! { None },
+    };
+
+    let revalidate = attr.revalidate.map(|secs| {
+        quote::quote! {
+            fn revalidate() -> Option<std::time::Duration> {
+                Some(std::time::Duration::from_secs(#secs))
+            }
+        }
+    });
+
+    // The static params are only used on the server for exporting the pages
+    let static_params = attr.static_params.map(|static_params_fn| {
+        quote::quote! {
+            fn static_params() -> ::hashira::types::BoxFuture<std::result::Result<std::vec::Vec<::hashira::routing::Params>, ::hashira::error::BoxError>> {
+                #[cfg(feature = "client")]
+                {
+                    std::unreachable!()
+                }
+
+                #[cfg(not(feature = "client"))]
+                {
+                    let fut = ::hashira::components::handler::call_static_params(#static_params_fn);
+                    std::boxed::Box::pin(fut)
+                }
+            }
+        }
+    });
+
     // TODO: the id should always produce the same result across server and client
     // if the code is the same. Currently we are exposing information
     // about the module where the component is declared, which may or not be relevant.
@@ -113,6 +221,14 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
                 #route
             }
 
+            fn layout() -> Option<::hashira::components::PageLayout> {
+                #layout
+            }
+
+            #revalidate
+
+            #static_params
+
             fn render<BASE>(ctx: ::hashira::app::RenderContext, body: ::hashira::web::Body)
                 -> ::hashira::types::BoxFuture<std::result::Result<::hashira::web::Response, ::hashira::error::BoxError>>
                 where
@@ -121,8 +237,72 @@ pub fn page_component_impl(attr: PageComponentAttr, item_fn: ItemFn) -> syn::Res
             }
         }
 
+        #url_fn
+
         #[yew::function_component]
         #[allow(non_camel_case_types)]
         #item_fn
     })
 }
+
+// Generates a function that takes the params of the route and returns the url to the page
+fn page_url_fn(route: &LitStr) -> syn::Result<TokenStream> {
+    let path = route.value();
+    let segments =
+        hashira_route::parse_segments(&path).map_err(|err| syn::Error::new(route.span(), err))?;
+
+    let mut args = vec![];
+    let mut pushes = vec![];
+
+    for segment in segments {
+        let (name, ty, optional, catch_all) = match segment {
+            RouteSegment::Static(part) => {
+                pushes.push(quote::quote! { url = url.push_segment(#part); });
+                continue;
+            }
+            RouteSegment::Param { name, ty, optional } => (name, ty, optional, false),
+            RouteSegment::CatchAll { name } => (name, None, false, true),
+        };
+
+        // Names like `post-id` are valid params but not valid arguments
+        let arg_name = name.replace(|c: char| !c.is_alphanumeric() && c != '_', "_");
+        let ident = syn::parse_str::<Ident>(&arg_name).map_err(|_| {
+            syn::Error::new(
+                route.span(),
+                format!("param `{name}` cannot be used as an argument of `url`"),
+            )
+        })?;
+
+        let ty = ty.map(|ty| Ident::new(ty.name(), Span::call_site()));
+        let arg_ty = match (ty, optional) {
+            (Some(ty), false) => quote::quote! { #ty },
+            (Some(ty), true) => quote::quote! { std::option::Option<#ty> },
+            (None, false) => quote::quote! { impl std::fmt::Display },
+            (None, true) => quote::quote! { std::option::Option<&str> },
+        };
+
+        args.push(quote::quote! { #ident: #arg_ty });
+        pushes.push(match (optional, catch_all) {
+            (true, _) => quote::quote! {
+                if let Some(value) = #ident {
+                    url = url.push_param(&value);
+                }
+            },
+            (false, true) => quote::quote! { url = url.push_catch_all(&#ident); },
+            (false, false) => quote::quote! { url = url.push_param(&#ident); },
+        });
+    }
+
+    let doc = format!("Returns the url to this page, `{path}`.");
+
+    Ok(quote::quote! {
+        #[doc = #doc]
+        #[allow(clippy::too_many_arguments)]
+        pub fn url(#(#args),*) -> ::hashira::routing::PageUrl {
+            #[allow(unused_mut)]
+            let mut url = ::hashira::routing::PageUrl::for_page::<Self>();
+            #(#pushes)*
+            url.build()
+        }
+    })
+}
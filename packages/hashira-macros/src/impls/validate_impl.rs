@@ -0,0 +1,173 @@
+// #[derive(Validate)]
+// #[validate(required, min_length = 3, max_length = 20, min = 1, max = 10, email, custom = "path", message = "...")]
+
+use proc_macro2::TokenStream;
+use syn::{spanned::Spanned, Data, DeriveInput, Expr, Fields, LitInt, LitStr};
+
+enum Rule {
+    Required,
+    MinLength(LitInt),
+    MaxLength(LitInt),
+    Min(Expr),
+    Max(Expr),
+    Email,
+    Custom(syn::Path),
+}
+
+struct FieldRules {
+    rules: Vec<Rule>,
+    message: Option<String>,
+}
+
+fn parse_field_rules(field: &syn::Field) -> syn::Result<FieldRules> {
+    let mut rules = vec![];
+    let mut message = None;
+
+    for attr in field.attrs.iter().filter(|x| x.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("required") {
+                rules.push(Rule::Required);
+            } else if meta.path.is_ident("min_length") {
+                rules.push(Rule::MinLength(meta.value()?.parse()?));
+            } else if meta.path.is_ident("max_length") {
+                rules.push(Rule::MaxLength(meta.value()?.parse()?));
+            } else if meta.path.is_ident("min") {
+                rules.push(Rule::Min(meta.value()?.parse()?));
+            } else if meta.path.is_ident("max") {
+                rules.push(Rule::Max(meta.value()?.parse()?));
+            } else if meta.path.is_ident("email") {
+                rules.push(Rule::Email);
+            } else if meta.path.is_ident("custom") {
+                let path: LitStr = meta.value()?.parse()?;
+                rules.push(Rule::Custom(path.parse()?));
+            } else if meta.path.is_ident("message") {
+                let msg: LitStr = meta.value()?.parse()?;
+                message = Some(msg.value());
+            } else {
+                return Err(meta.error("unknown validation rule"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(FieldRules { rules, message })
+}
+
+// Returns the name of the field used by serde, so the errors match the form inputs.
+fn get_field_name(field: &syn::Field) -> syn::Result<String> {
+    let mut name = field.ident.as_ref().unwrap().to_string();
+
+    for attr in field.attrs.iter().filter(|x| x.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let rename: LitStr = meta.value()?.parse()?;
+                name = rename.value();
+            } else if meta.input.peek(syn::Token![=]) {
+                // Skip other `key = value` serde attributes
+                let _: Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _ = meta.parse_nested_meta(|_| Ok(()));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(name)
+}
+
+/// Implementation of `#[derive(Validate)]`
+pub fn validate_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`Validate` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "`Validate` can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut checks = vec![];
+
+    for field in fields {
+        let FieldRules { rules, message } = parse_field_rules(field)?;
+        let ident = field.ident.as_ref().unwrap();
+        let name = get_field_name(field)?;
+
+        for rule in rules {
+            let (cond, default_message) = match rule {
+                Rule::Required => (
+                    quote::quote! { ::hashira::actions::rules::required(&self.#ident) },
+                    format!("{name} is required"),
+                ),
+                Rule::MinLength(n) => (
+                    quote::quote! { ::hashira::actions::rules::min_length(&self.#ident, #n) },
+                    format!("{name} must have at least {n} characters"),
+                ),
+                Rule::MaxLength(n) => (
+                    quote::quote! { ::hashira::actions::rules::max_length(&self.#ident, #n) },
+                    format!("{name} must have at most {n} characters"),
+                ),
+                Rule::Min(expr) => (
+                    quote::quote! { ::hashira::actions::rules::min(&self.#ident, #expr) },
+                    format!(
+                        "{name} must be greater than or equal to {}",
+                        quote::quote! { #expr }
+                    ),
+                ),
+                Rule::Max(expr) => (
+                    quote::quote! { ::hashira::actions::rules::max(&self.#ident, #expr) },
+                    format!(
+                        "{name} must be less than or equal to {}",
+                        quote::quote! { #expr }
+                    ),
+                ),
+                Rule::Email => (
+                    quote::quote! { ::hashira::actions::rules::email(&self.#ident) },
+                    format!("{name} must be a valid email"),
+                ),
+                Rule::Custom(path) => {
+                    // Custom rules returns its own message
+                    checks.push(quote::quote! {
+                        if let ::std::result::Result::Err(msg) = #path(&self.#ident) {
+                            errors.add(#name, msg);
+                        }
+                    });
+                    continue;
+                }
+            };
+
+            let message = message.clone().unwrap_or(default_message);
+            checks.push(quote::quote! {
+                if !(#cond) {
+                    errors.add(#name, #message);
+                }
+            });
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote::quote! {
+        #[automatically_derived]
+        impl #impl_generics ::hashira::actions::Validate for #name #ty_generics #where_clause {
+            fn validate(&self) -> ::std::result::Result<(), ::hashira::actions::ValidationErrors> {
+                #[allow(unused_mut)]
+                let mut errors = ::hashira::actions::ValidationErrors::new();
+                #(#checks)*
+                errors.into_result()
+            }
+        }
+    })
+}
@@ -8,13 +8,31 @@ use syn::spanned::Spanned;
 /// Provides an implementation of `PageComponent`.
 ///
 /// # Usage
-/// You need to pass the route of the page and an optional loader
-/// which renders the page, if no loader is specified, a default loader
-/// will be provided that renders the component providing `Default` of the props.
+/// You need to pass the route of the page and optionally a `render` function
+/// which renders the page or a `loader` which returns the props of the page,
+/// if none is specified the component is rendered using the `Default` of the props.
 ///
 /// - `#[page_component("/route")]`
+/// - `#[page_component("/route", render = "path::to::function")]`
 /// - `#[page_component("/route", loader = "path::to::function")]`
-/// - `#[page_component(None, loader = "path::to::function")]`
+/// - `#[page_component(None, render = "path::to::function")]`
+/// - `#[page_component("/route", layout = "path::to::Layout")]`
+/// - `#[page_component("/route/:id", static_params = "path::to::function")]`
+/// - `#[page_component("/route", revalidate = 60)]`
+///
+/// # Loaders
+/// A loader is a server only async function that can take any `FromRequest` arguments
+/// and returns the props of the page. When compiling for the `client` the loader
+/// is never called, so it can be marked with `#[cfg(not(feature = "client"))]`.
+///
+/// # Static params
+/// When exporting the app as static files the pages with dynamic routes are rendered
+/// once for each of the `Params` returned by the `static_params` async function,
+/// like the loader this function is only called on the server.
+///
+/// # Revalidate
+/// The number of seconds after which the stored render of the page is regenerated
+/// in the background, the page is only stored if the app uses `StaticRegeneration`.
 ///
 /// # Example
 ///
@@ -30,6 +48,19 @@ use syn::spanned::Spanned;
 ///         "Hello World!"
 ///     }
 /// }
+///
+/// #[cfg(not(feature = "client"))]
+/// async fn load_user(Inject(pool): Inject<DbPool>, params: Params) -> Result<UserPageProps, Error> {
+///     let user = find_user(&pool, params.get("id")).await?;
+///     Ok(UserPageProps { user })
+/// }
+///
+/// #[page_component("/users/:id", loader = "load_user")]
+/// fn UserPage(props: &UserPageProps) -> yew::Html {
+///     yew::html! {
+///         <h1>{&props.user.name}</h1>
+///     }
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn page_component(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -48,6 +79,23 @@ pub fn page_component(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// You can decorate a function using any:
 /// - `[action]` to create a server action with a generated route
 /// - `[action("/route/to/action")]` to create a server action with an explicit route.
+/// - `[action("/route/to/action", csrf = false)]` to not verify the CSRF token of the requests.
+/// - `[action(guard = path::to::guard)]` to check the request before calling the action, can be used multiple times.
+///
+/// # Guards
+/// A guard is an `async fn(RequestContext) -> Result<(), ServerError>` or any `ActionGuard`,
+/// the guards run in order before the action and the first error is returned without calling the action.
+/// Like the action, the guards are only called on the server.
+///
+/// # Calling the action
+/// Besides the `Action` implementation, an associated `fetch` function is generated to call
+/// the action from any async client code, it takes the input of the action
+/// (`Form`, `Json`, `Query` or `Valid`) and returns the data of the response,
+/// for example `CreateTodo(form: Form<NewTodo>) -> Json<Todo>` generates
+/// `CreateTodo::fetch(input: Form<NewTodo>) -> hashira::Result<Todo>`.
+/// A `Multipart` input is sent as an `AnyForm`, and the input can only be sent
+/// if it can be serialized, otherwise the action can only be called using `use_action`.
+/// An action can only take one input.
 ///
 /// # Example
 /// ```rs,no_run
@@ -68,6 +116,48 @@ pub fn action(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// Provides an implementation of `Validate`.
+///
+/// # Usage
+/// Each field can declare its rules using `#[validate(...)]`, the errors are
+/// reported using the name of the field or its `#[serde(rename = "...")]`.
+///
+/// - `required`, the value is not empty or `None`
+/// - `min_length = n` and `max_length = n`, the number of characters or items
+/// - `min = x` and `max = x`, the bounds of a number
+/// - `email`, the value looks like an email
+/// - `custom = "path::to::function"`, a `fn(&T) -> Result<(), String>`
+/// - `message = "..."`, the error message of the rules of the field
+///
+/// # Example
+/// ```rs,no_run
+/// #[derive(Deserialize, Validate)]
+/// struct NewUser {
+///     #[validate(required, max_length = 20)]
+///     username: String,
+///
+///     #[validate(email, message = "invalid email")]
+///     email: String,
+///
+///     #[validate(min = 18)]
+///     age: u32,
+/// }
+///
+/// #[action]
+/// async fn CreateUser(form: Valid<Form<NewUser>>) -> Json<User> {
+///     todo!()
+/// }
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn validate(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+
+    match impls::validate_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
 /// Marks a method as a page component render function.
 /// 
 /// # Remarks
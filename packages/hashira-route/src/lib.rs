@@ -0,0 +1,203 @@
+//! The route path grammar shared by `hashira` and `hashira-macros`.
+
+use std::fmt::Display;
+
+macro_rules! param_types {
+    ($($variant:ident => $ty:ty, $name:literal),* $(,)?) => {
+        /// The type a route param must be parsed to, declared as `{name:type}`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ParamType {
+            $(
+                #[doc = concat!("The param must be a `", $name, "`.")]
+                $variant,
+            )*
+        }
+
+        impl ParamType {
+            /// Returns the param type with the given name.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(ParamType::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the name of this type, which is also the name of the rust type.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ParamType::$variant => $name,)*
+                }
+            }
+
+            /// Returns `true` if the value can be parsed to this type.
+            pub fn is_valid(&self, value: &str) -> bool {
+                match self {
+                    $(ParamType::$variant => value.parse::<$ty>().is_ok(),)*
+                }
+            }
+        }
+    };
+}
+
+param_types! {
+    U8 => u8, "u8",
+    U16 => u16, "u16",
+    U32 => u32, "u32",
+    U64 => u64, "u64",
+    U128 => u128, "u128",
+    Usize => usize, "usize",
+    I8 => i8, "i8",
+    I16 => i16, "i16",
+    I32 => i32, "i32",
+    I64 => i64, "i64",
+    I128 => i128, "i128",
+    Isize => isize, "isize",
+    F32 => f32, "f32",
+    F64 => f64, "f64",
+    Bool => bool, "bool",
+}
+
+impl Display for ParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A segment of a route path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteSegment {
+    /// A segment that must match exactly.
+    Static(String),
+
+    /// A segment captured as a param: `{name}`, `{name:type}`, `{name?}` or `:name`.
+    Param {
+        /// The name of the param.
+        name: String,
+
+        /// The type the value must be parsed to.
+        ty: Option<ParamType>,
+
+        /// Whether if the segment can be omitted, only allowed as the last segment.
+        optional: bool,
+    },
+
+    /// A param that captures the rest of the path: `{*name}` or `*name`.
+    CatchAll {
+        /// The name of the param.
+        name: String,
+    },
+}
+
+/// Parses the segments of the given route path.
+pub fn parse_segments(path: &str) -> Result<Vec<RouteSegment>, String> {
+    let Some(rest) = path.strip_prefix('/') else {
+        return Err(format!("route path must start with `/`, but was: {}", path));
+    };
+
+    let mut segments: Vec<RouteSegment> = vec![];
+
+    for part in split_segments(rest) {
+        if segments.last().map(is_trailing).unwrap_or(false) {
+            return Err(format!(
+                "optional and catch-all params must be the last segment of the route: {path}"
+            ));
+        }
+
+        if part.is_empty() {
+            return Err(format!("route path cannot contain empty segments: {path}"));
+        }
+
+        let segment = parse_segment(part).map_err(|err| format!("{err}: {path}"))?;
+
+        if let RouteSegment::Param { name, .. } | RouteSegment::CatchAll { name } = &segment {
+            let is_duplicated = segments.iter().any(|s| {
+                matches!(s, RouteSegment::Param { name: n, .. } | RouteSegment::CatchAll { name: n } if n == name)
+            });
+
+            if is_duplicated {
+                return Err(format!("duplicated param `{name}` in route: {path}"));
+            }
+        }
+
+        segments.push(segment);
+    }
+
+    Ok(segments)
+}
+
+/// Splits the given path without the leading `/` in segments, the root path `/` have no segments.
+pub fn split_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(move |_| !path.is_empty())
+}
+
+fn is_trailing(segment: &RouteSegment) -> bool {
+    matches!(
+        segment,
+        RouteSegment::Param { optional: true, .. } | RouteSegment::CatchAll { .. }
+    )
+}
+
+fn parse_segment(part: &str) -> Result<RouteSegment, String> {
+    if let Some(name) = part.strip_prefix(':') {
+        return Ok(RouteSegment::Param {
+            name: parse_param_name(name)?,
+            ty: None,
+            optional: false,
+        });
+    }
+
+    if let Some(name) = part.strip_prefix('*') {
+        return Ok(RouteSegment::CatchAll {
+            name: parse_param_name(name)?,
+        });
+    }
+
+    if let Some(inner) = part.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        if let Some(name) = inner.strip_prefix('*') {
+            return Ok(RouteSegment::CatchAll {
+                name: parse_param_name(name)?,
+            });
+        }
+
+        let (inner, optional) = match inner.strip_suffix('?') {
+            Some(inner) => (inner, true),
+            None => (inner, false),
+        };
+
+        let (name, ty) = match inner.split_once(':') {
+            Some((name, ty)) => {
+                let ty = ParamType::from_name(ty)
+                    .ok_or_else(|| format!("unknown type `{ty}` for param `{name}`"))?;
+                (name, Some(ty))
+            }
+            None => (inner, None),
+        };
+
+        return Ok(RouteSegment::Param {
+            name: parse_param_name(name)?,
+            ty,
+            optional,
+        });
+    }
+
+    if part.contains(['{', '}', ':', '*']) {
+        return Err(format!(
+            "invalid route segment `{part}`, params must take the full segment"
+        ));
+    }
+
+    Ok(RouteSegment::Static(part.to_owned()))
+}
+
+// Param names can contain any character not used by the route syntax, like `post-id` or `slug.v2`
+fn parse_param_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err(String::from("param name cannot be empty"));
+    }
+
+    if name.contains(['{', '}', ':', '*', '?']) {
+        return Err(format!("invalid param name `{name}`"));
+    }
+
+    Ok(name.to_owned())
+}
@@ -0,0 +1,253 @@
+use crate::{components::HASHIRA_CSRF_COOKIE, web::RequestExt};
+use http::{HeaderValue, Method, Request};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// The token that protects the actions against cross-site request forgery.
+///
+/// Each client receives a random token in a cookie when requests a page, the client
+/// reads the token from the cookie. `use_action` and `ActionForm` send the token
+/// with each request, in the `x-hashira-csrf-token` header or the `_csrf` field of a form,
+/// and the action is rejected with a `403 Forbidden` if the token doesn't match the cookie.
+///
+/// An `ActionForm` rendered on the server includes the token so it can be submitted
+/// before the page is hydrated, those pages are never cached, stored or exported.
+///
+/// Actions can opt-out using `#[action(csrf = false)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    /// Returns the token.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns the token stored in the cookies of the request, if any.
+    pub fn from_request<B>(req: &Request<B>) -> Option<Self> {
+        let cookie = req.cookie(HASHIRA_CSRF_COOKIE)?;
+        let token = cookie.value();
+
+        if token.is_empty() {
+            return None;
+        }
+
+        Some(CsrfToken(token.to_owned()))
+    }
+
+    /// Generates a new random token.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn generate() -> Self {
+        use std::fmt::Write;
+
+        let mut bytes = [0u8; 32];
+        getrandom::getrandom(&mut bytes).expect("failed to generate csrf token");
+
+        let token = bytes.iter().fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        });
+
+        CsrfToken(token)
+    }
+
+    /// Returns the `Set-Cookie` header value that stores this token.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn to_cookie(&self) -> HeaderValue {
+        // The cookie is read by the client to send the token
+        let cookie = cookie::Cookie::build(HASHIRA_CSRF_COOKIE, self.0.as_str())
+            .path("/")
+            .same_site(cookie::SameSite::Lax)
+            .finish();
+
+        HeaderValue::from_str(&cookie.to_string()).expect("invalid cookie")
+    }
+
+    /// Returns the token of the current client, read from the cookies.
+    #[cfg(target_arch = "wasm32")]
+    pub fn current() -> Option<Self> {
+        use wasm_bindgen::JsCast;
+
+        let document = web_sys::window()?.document()?;
+        let cookies = document
+            .dyn_into::<web_sys::HtmlDocument>()
+            .ok()?
+            .cookie()
+            .ok()?;
+
+        cookie::Cookie::split_parse(cookies)
+            .filter_map(|x| x.ok())
+            .find(|x| x.name() == HASHIRA_CSRF_COOKIE)
+            .map(|x| CsrfToken(x.value().to_owned()))
+            .filter(|x| !x.0.is_empty())
+    }
+
+    /// Returns the token of the current client, read from the cookies.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn current() -> Option<Self> {
+        unreachable!("client only function")
+    }
+}
+
+/// Records whether the token of the client was rendered in the response of a request,
+/// shared by the request and its response.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmbeddedCsrfToken(Arc<AtomicBool>);
+
+impl EmbeddedCsrfToken {
+    /// Marks the token as rendered.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub fn mark(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the token was rendered, the response must not be shared with other clients.
+    pub fn is_embedded(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returns `true` if the token of the client was rendered in the response with the given extensions.
+pub(crate) fn has_embedded_csrf_token(extensions: &http::Extensions) -> bool {
+    extensions
+        .get::<EmbeddedCsrfToken>()
+        .map(|x| x.is_embedded())
+        .unwrap_or_default()
+}
+
+/// Returns `true` if the requests with the given method need to be verified.
+#[cfg_attr(feature = "client", allow(dead_code))]
+pub(crate) fn requires_csrf_token(method: &Method) -> bool {
+    !matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+#[cfg(not(feature = "client"))]
+pub(crate) use server::*;
+
+#[cfg(not(feature = "client"))]
+mod server {
+    use super::{requires_csrf_token, CsrfToken};
+    use crate::{
+        app::RequestContext,
+        components::{HASHIRA_CSRF_FIELD, HASHIRA_CSRF_HEADER},
+        error::ServerError,
+        web::{Body, RequestExt},
+    };
+    use bytes::Bytes;
+    use http::StatusCode;
+    use std::convert::Infallible;
+
+    /// Verifies the token sent with the request matches the token of the client.
+    ///
+    /// Returns the body of the request, that may be read to find the token in a form.
+    pub(crate) async fn verify_csrf_token(
+        ctx: &RequestContext,
+        body: Body,
+    ) -> Result<Body, ServerError> {
+        let req = ctx.request();
+
+        if !requires_csrf_token(req.method()) {
+            return Ok(body);
+        }
+
+        let Some(expected) = CsrfToken::from_request(req) else {
+            return Err(forbidden());
+        };
+
+        if let Some(token) = req.headers().get(HASHIRA_CSRF_HEADER) {
+            return match token.as_bytes() == expected.as_str().as_bytes() {
+                true => Ok(body),
+                false => Err(forbidden()),
+            };
+        }
+
+        // Native form submissions send the token in a field
+        let Some(content_type) = req.content_type() else {
+            return Err(forbidden());
+        };
+
+        let bytes = body
+            .into_bytes()
+            .await
+            .map_err(|err| ServerError::new(StatusCode::BAD_REQUEST, err))?;
+
+        let token = match content_type.essence_str() {
+            s if s == mime::APPLICATION_WWW_FORM_URLENCODED.essence_str() => {
+                serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+                    .ok()
+                    .and_then(|fields| {
+                        fields
+                            .into_iter()
+                            .find(|(name, _)| name == HASHIRA_CSRF_FIELD)
+                            .map(|(_, value)| value)
+                    })
+            }
+            s if s == mime::MULTIPART_FORM_DATA.essence_str() => {
+                let boundary = content_type
+                    .get_param(mime::BOUNDARY)
+                    .map(|x| x.as_str().to_owned());
+
+                match boundary {
+                    Some(boundary) => get_multipart_field(bytes.clone(), boundary).await,
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        match token {
+            Some(token) if token == expected.as_str() => Ok(Body::from(bytes)),
+            _ => Err(forbidden()),
+        }
+    }
+
+    async fn get_multipart_field(bytes: Bytes, boundary: String) -> Option<String> {
+        use multer_derive::multer::Multipart;
+
+        let stream = futures::stream::once(async move { Ok::<_, Infallible>(bytes) });
+        let mut multipart = Multipart::new(stream, boundary);
+
+        while let Ok(Some(field)) = multipart.next_field().await {
+            if field.name() == Some(HASHIRA_CSRF_FIELD) {
+                return field.text().await.ok();
+            }
+        }
+
+        None
+    }
+
+    fn forbidden() -> ServerError {
+        ServerError::new(StatusCode::FORBIDDEN, "invalid csrf token")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{requires_csrf_token, CsrfToken};
+    use crate::components::HASHIRA_CSRF_COOKIE;
+    use http::{Method, Request};
+
+    #[test]
+    fn csrf_token_test() {
+        let token = CsrfToken::generate();
+        assert_eq!(token.as_str().len(), 64);
+        assert_ne!(token, CsrfToken::generate());
+
+        let req = Request::get("/")
+            .header(
+                "cookie",
+                format!("{HASHIRA_CSRF_COOKIE}={}", token.as_str()),
+            )
+            .body(())
+            .unwrap();
+
+        assert_eq!(CsrfToken::from_request(&req), Some(token));
+        assert!(requires_csrf_token(&Method::POST));
+        assert!(!requires_csrf_token(&Method::GET));
+    }
+}
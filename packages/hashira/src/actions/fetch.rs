@@ -0,0 +1,130 @@
+use super::{into_request_config::IntoRequestConfig, Action, RequestOptions};
+use crate::web::IntoJsonResponse;
+
+/// Calls an action on the server and returns its data.
+///
+/// Unlike `use_action` this can be called from any async code in the client,
+/// the `#[action]` macro generates an associated `fetch` function that calls this with the input of the action.
+///
+/// # Example
+/// ```rust,ignore
+/// #[action]
+/// async fn CreateTodo(form: Form<NewTodo>) -> Json<Todo> {
+///     todo!()
+/// }
+///
+/// let todo = fetch_action::<CreateTodo, _>(Form(new_todo), RequestOptions::new()).await?;
+/// // or using the generated function
+/// let todo = CreateTodo::fetch(Form(new_todo)).await?;
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unused_variables)]
+pub async fn fetch_action<A, T>(
+    obj: T,
+    options: RequestOptions,
+) -> crate::Result<<A::Response as IntoJsonResponse>::Data>
+where
+    A: Action,
+    T: IntoRequestConfig,
+{
+    unreachable!("client only function")
+}
+
+/// Calls an action on the server and returns its data.
+///
+/// Unlike `use_action` this can be called from any async code in the client,
+/// the `#[action]` macro generates an associated `fetch` function that calls this with the input of the action.
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_action<A, T>(
+    obj: T,
+    options: RequestOptions,
+) -> crate::Result<<A::Response as IntoJsonResponse>::Data>
+where
+    A: Action,
+    T: IntoRequestConfig,
+{
+    let request = create_action_request::<A, T>(obj, options)?;
+    crate::utils::wasm::fetch_json(request).await
+}
+
+/// Creates the request to call the given action.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn create_action_request<A, T>(
+    obj: T,
+    options: RequestOptions,
+) -> Result<web_sys::Request, crate::error::BoxError>
+where
+    A: Action,
+    T: IntoRequestConfig,
+{
+    use super::into_request_config::RequestInitConfig;
+    use super::CsrfToken;
+    use crate::components::{HASHIRA_ACTION_HEADER, HASHIRA_CSRF_HEADER};
+    use crate::error::JsError;
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{Headers, RequestInit};
+
+    let request_config = obj.into_request_config(&options)?;
+    let RequestInitConfig {
+        init,
+        search_params,
+    } = request_config;
+
+    let mut init = init.unwrap_or_else(|| RequestInit::new());
+    let headers = match js_sys::Reflect::get(&init, &JsValue::from("headers")) {
+        Ok(x) => {
+            // If is falsy means it can be null or undefined, so we just create an instance
+            if x.is_falsy() {
+                Headers::new().map_err(JsError::new)?
+            } else {
+                // Otherwise we try to convert and return a new header if fail
+                match x.dyn_into::<Headers>() {
+                    Ok(headers) => headers,
+                    Err(err) => {
+                        log::debug!("failed to cast property `headers` to Headers type: {err:?}");
+                        Headers::new().map_err(JsError::new)?
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            log::debug!("failed to get `RequestInit::headers`: {err:?}");
+            Headers::new().map_err(JsError::new)?
+        }
+    };
+
+    let mut last_name = None;
+    for (name, value) in options.headers {
+        if let Some(name) = name {
+            last_name = Some(name);
+        }
+
+        let key = last_name.as_ref().unwrap();
+        headers
+            .append(key.as_str(), value.to_str()?)
+            .map_err(JsError::new)?;
+    }
+
+    // Tells the server this is not a native form submission
+    headers
+        .set(HASHIRA_ACTION_HEADER, "true")
+        .map_err(JsError::new)?;
+
+    if let Some(token) = CsrfToken::current() {
+        headers
+            .set(HASHIRA_CSRF_HEADER, token.as_str())
+            .map_err(JsError::new)?;
+    }
+
+    init.headers(&headers);
+    init.method(options.method.as_str());
+
+    let mut url = A::route().to_owned();
+
+    if let Some(search_params) = search_params {
+        url.push_str(&format!("?{search}", search = search_params.to_string()));
+    }
+
+    let request = web_sys::Request::new_with_str_and_init(&url, &init).map_err(JsError::new)?;
+    Ok(request)
+}
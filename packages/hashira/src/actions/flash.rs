@@ -0,0 +1,294 @@
+use super::{Action, ValidationErrors};
+use crate::{
+    components::{PageError, HASHIRA_ACTION_FLASH_COOKIE, HASHIRA_ACTION_HEADER},
+    error::ServerError,
+    web::{IntoJsonResponse, Request, RequestExt},
+};
+use cookie::Cookie;
+use http::{header, HeaderValue, Method, Uri};
+use serde::{Deserialize, Serialize};
+
+/// The result of an action sent by a native form submission.
+///
+/// When a form is submitted before the wasm loads, or with javascript disabled,
+/// the action responds with a redirect to the page that sent the form and stores
+/// its result in a cookie. The page is rendered with the result and the cookie is removed,
+/// so `use_action` returns the result as if the action was called from the client.
+///
+/// Browsers drop cookies larger than 4KB, so if the result is too large only
+/// the status of the action is kept and `use_action` has no data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionFlash {
+    /// The route of the action.
+    pub action: String,
+
+    /// The json returned by the action if succeed.
+    pub data: Option<serde_json::Value>,
+
+    /// The error returned by the action if failed.
+    pub error: Option<PageError>,
+
+    /// Whether the data or the error message were too large and were omitted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl ActionFlash {
+    /// Returns the action result stored in the request cookies, if any.
+    pub fn from_request<B>(req: &Request<B>) -> Option<Self> {
+        let cookie = req.cookie(HASHIRA_ACTION_FLASH_COOKIE)?;
+
+        match serde_json::from_str(cookie.value()) {
+            Ok(flash) => Some(flash),
+            Err(err) => {
+                log::warn!("invalid action flash cookie: {err}");
+                None
+            }
+        }
+    }
+
+    /// Returns the result of the given action, if this is the result of that action.
+    pub fn result_for<A: Action>(
+        &self,
+    ) -> Option<crate::Result<<A::Response as IntoJsonResponse>::Data>> {
+        if self.action != A::route() {
+            return None;
+        }
+
+        if let Some(PageError { status, message }) = &self.error {
+            if let Some(errors) = message.as_deref().and_then(ValidationErrors::from_json) {
+                return Some(Err(errors.into()));
+            }
+
+            let message = message
+                .clone()
+                .or_else(|| status.canonical_reason().map(String::from))
+                .unwrap_or_default();
+
+            return Some(Err(ServerError::new(*status, message).into()));
+        }
+
+        // The action succeed but we don't have the data
+        if self.truncated {
+            return None;
+        }
+
+        let data = self.data.clone().unwrap_or_default();
+        Some(serde_json::from_value(data).map_err(Into::into))
+    }
+}
+
+/// Returns `true` if the request was sent by the browser submitting a form and not by the client `fetch`.
+pub fn is_native_form_submit<B>(req: &Request<B>) -> bool {
+    if req.headers().contains_key(HASHIRA_ACTION_HEADER) || req.method() != Method::POST {
+        return false;
+    }
+
+    match req.content_type() {
+        Some(mime) => {
+            mime.essence_str() == mime::APPLICATION_WWW_FORM_URLENCODED.essence_str()
+                || mime.essence_str() == mime::MULTIPART_FORM_DATA.essence_str()
+        }
+        None => false,
+    }
+}
+
+/// Returns the path of the page that sent the request, defaults to `/`.
+///
+/// Only the path of the `Referer` is used, so we never redirect to other site.
+#[cfg_attr(feature = "client", allow(dead_code))]
+pub(crate) fn get_referer_path<B>(req: &Request<B>) -> String {
+    req.headers()
+        .get(header::REFERER)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.parse::<Uri>().ok())
+        .and_then(|uri| uri.path_and_query().map(|x| x.as_str().to_owned()))
+        .filter(|path| path.starts_with('/') && !path.starts_with("//"))
+        .unwrap_or_else(|| String::from("/"))
+}
+
+#[cfg(not(feature = "client"))]
+pub(crate) use server::*;
+
+#[cfg(not(feature = "client"))]
+mod server {
+    use super::ActionFlash;
+    use crate::{
+        components::{PageError, HASHIRA_ACTION_FLASH_COOKIE},
+        web::{Body, Response},
+    };
+    use cookie::{Cookie, SameSite};
+    use http::{header, HeaderValue, StatusCode};
+
+    // The flash only needs to live until the redirect is followed.
+    const FLASH_MAX_AGE: cookie::time::Duration = cookie::time::Duration::minutes(1);
+
+    // Browsers drop cookies larger than 4096 bytes, including the name and the attributes.
+    const FLASH_MAX_COOKIE_SIZE: usize = 4000;
+
+    /// Creates a redirect to the given location with the result of the action.
+    pub(crate) async fn redirect_with_flash(
+        action: &str,
+        location: &str,
+        res: Response,
+    ) -> Response {
+        // The action already decided where to go
+        if res.status().is_redirection() {
+            return res;
+        }
+
+        // We keep the headers set by the action, like the cookies or the invalidated tags
+        let (mut parts, body) = res.into_parts();
+        let status = parts.status;
+        let bytes = body.into_bytes().await.unwrap_or_default();
+
+        let flash = if status.is_success() {
+            ActionFlash {
+                action: action.to_owned(),
+                data: serde_json::from_slice(&bytes).ok(),
+                error: None,
+                truncated: false,
+            }
+        } else {
+            let message = String::from_utf8(bytes.to_vec())
+                .ok()
+                .filter(|x| !x.is_empty());
+
+            ActionFlash {
+                action: action.to_owned(),
+                data: None,
+                error: Some(PageError { status, message }),
+                truncated: false,
+            }
+        };
+
+        parts.status = StatusCode::SEE_OTHER;
+
+        let headers = &mut parts.headers;
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+        headers.insert(
+            header::LOCATION,
+            HeaderValue::from_str(location).expect("invalid redirect location"),
+        );
+
+        // If the result is too large we only keep the status
+        let cookie = flash_cookie(&flash).or_else(|| {
+            log::warn!("action result of `{action}` is too large to be stored in a cookie");
+            let error = flash.error.as_ref().map(|error| PageError {
+                status: error.status,
+                message: None,
+            });
+
+            flash_cookie(&ActionFlash {
+                action: action.to_owned(),
+                data: None,
+                error,
+                truncated: true,
+            })
+        });
+
+        if let Some(value) = cookie {
+            headers.append(header::SET_COOKIE, value);
+        }
+
+        Response::from_parts(parts, Body::empty())
+    }
+
+    // Returns the `Set-Cookie` header value with the flash, if fits in a cookie.
+    pub(super) fn flash_cookie(flash: &ActionFlash) -> Option<HeaderValue> {
+        let json = match serde_json::to_string(flash) {
+            Ok(json) => json,
+            Err(err) => {
+                log::error!("failed to serialize action result: {err}");
+                return None;
+            }
+        };
+
+        let cookie = Cookie::build(HASHIRA_ACTION_FLASH_COOKIE, json)
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(FLASH_MAX_AGE)
+            .finish();
+
+        let cookie = cookie.encoded().to_string();
+        if cookie.len() > FLASH_MAX_COOKIE_SIZE {
+            return None;
+        }
+
+        HeaderValue::from_str(&cookie).ok()
+    }
+}
+
+/// Returns the `Set-Cookie` header value that removes the flash cookie.
+pub(crate) fn remove_flash_cookie() -> HeaderValue {
+    let mut cookie = Cookie::build(HASHIRA_ACTION_FLASH_COOKIE, "")
+        .path("/")
+        .finish();
+    cookie.make_removal();
+    HeaderValue::from_str(&cookie.to_string()).expect("invalid cookie")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_referer_path, is_native_form_submit};
+    use crate::components::HASHIRA_ACTION_HEADER;
+    use crate::web::Request;
+
+    #[test]
+    fn native_form_submit_test() {
+        let req = Request::post("/action")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(())
+            .unwrap();
+        assert!(is_native_form_submit(&req));
+
+        let req = Request::post("/action")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .header(HASHIRA_ACTION_HEADER, "true")
+            .body(())
+            .unwrap();
+        assert!(!is_native_form_submit(&req));
+
+        let req = Request::post("/action")
+            .header("content-type", "application/json")
+            .body(())
+            .unwrap();
+        assert!(!is_native_form_submit(&req));
+    }
+
+    #[test]
+    fn referer_path_test() {
+        let req = Request::post("/action")
+            .header("referer", "http://localhost:5000/todos?page=2")
+            .body(())
+            .unwrap();
+        assert_eq!(get_referer_path(&req), "/todos?page=2");
+
+        let req = Request::post("/action").body(()).unwrap();
+        assert_eq!(get_referer_path(&req), "/");
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn large_flash_test() {
+        use super::{redirect_with_flash, ActionFlash};
+        use crate::web::{Body, Response};
+        use cookie::Cookie;
+        use http::{header, StatusCode};
+
+        let data = serde_json::to_string(&"a".repeat(5000)).unwrap();
+        let res = redirect_with_flash("/api/action", "/", Response::new(Body::from(data))).await;
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+
+        let set_cookie = res.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(set_cookie.len() < 4096);
+
+        let cookie = Cookie::parse_encoded(set_cookie).unwrap();
+        let flash = serde_json::from_str::<ActionFlash>(cookie.value()).unwrap();
+        assert!(flash.truncated);
+        assert_eq!(flash.data, None);
+        assert_eq!(flash.error, None);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{app::RequestContext, error::ServerError, types::BoxFuture};
+use std::future::Future;
+
+/// A check that runs before an action is called.
+///
+/// If the guard returns an error the action is not called and the error is returned instead.
+/// Any `async fn(RequestContext) -> Result<(), ServerError>` is a guard.
+///
+/// # Example
+/// ```rust,ignore
+/// async fn is_admin(ctx: RequestContext) -> Result<(), ServerError> {
+///     match ctx.request().cookie("role") {
+///         Some(role) if role.value() == "admin" => Ok(()),
+///         _ => Err(ServerError::from_status(StatusCode::FORBIDDEN)),
+///     }
+/// }
+///
+/// #[action(guard = is_admin)]
+/// async fn DeleteUser(form: Form<DeleteUserInput>) -> Json<()> {
+///     todo!()
+/// }
+///
+/// // Or when registering the action
+/// App::new().action_with_guard::<DeleteUser, _>(is_admin)
+/// ```
+pub trait ActionGuard: Send + Sync + 'static {
+    /// Checks if the request can call the action.
+    fn check(&self, ctx: RequestContext) -> BoxFuture<Result<(), ServerError>>;
+}
+
+impl<F, Fut> ActionGuard for F
+where
+    F: Fn(RequestContext) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), ServerError>> + Send + 'static,
+{
+    fn check(&self, ctx: RequestContext) -> BoxFuture<Result<(), ServerError>> {
+        Box::pin((self)(ctx))
+    }
+}
@@ -23,3 +23,67 @@ where
     let res = handler.call(args).await;
     Ok(res)
 }
+
+/// Calls the action and returns its json response.
+///
+/// When the action is called by a native form submission the response is a redirect
+/// to the page that sent the form, with the result of the action in a flash cookie.
+#[cfg(not(feature = "client"))]
+pub(crate) async fn handle_action<A>(
+    ctx: RequestContext,
+    body: Body,
+    guard: Option<std::sync::Arc<dyn super::ActionGuard>>,
+) -> crate::web::Response
+where
+    A: super::Action,
+{
+    use super::{get_referer_path, is_native_form_submit, redirect_with_flash};
+    use crate::web::IntoResponse;
+
+    // We redirect back to the page that sent the form
+    let redirect_to = is_native_form_submit(ctx.request()).then(|| get_referer_path(ctx.request()));
+
+    let res = match check_action::<A>(&ctx, body, guard).await {
+        Ok(body) => call_action_json::<A>(ctx, body).await,
+        Err(err) => err.into_response(),
+    };
+
+    match redirect_to {
+        Some(location) => redirect_with_flash(A::route(), &location, res).await,
+        None => res,
+    }
+}
+
+// Verifies the request can call the action, returns the body to pass to the action.
+#[cfg(not(feature = "client"))]
+async fn check_action<A>(
+    ctx: &RequestContext,
+    mut body: Body,
+    guard: Option<std::sync::Arc<dyn super::ActionGuard>>,
+) -> Result<Body, crate::error::ServerError>
+where
+    A: super::Action,
+{
+    if A::csrf_protection() {
+        body = super::verify_csrf_token(ctx, body).await?;
+    }
+
+    A::guard(ctx.clone()).await?;
+
+    if let Some(guard) = guard {
+        guard.check(ctx.clone()).await?;
+    }
+
+    Ok(body)
+}
+
+#[cfg(not(feature = "client"))]
+async fn call_action_json<A>(ctx: RequestContext, body: Body) -> crate::web::Response
+where
+    A: super::Action,
+{
+    use crate::web::IntoJsonResponse;
+
+    let output = crate::try_response!(A::call(ctx, body).await);
+    crate::try_response!(output.into_serialized_response())
+}
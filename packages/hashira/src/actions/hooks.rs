@@ -1,15 +1,24 @@
-use super::{into_request_config::IntoRequestConfig, Action};
-use crate::{error::BoxError, web::IntoJsonResponse};
+use super::{into_request_config::IntoRequestConfig, Action, RetryPolicy, ValidationErrors};
+use crate::{context::PageDataHandle, error::BoxError, web::IntoJsonResponse};
 use http::{HeaderMap, HeaderName, HeaderValue, Method};
-use std::{fmt::Debug, marker::PhantomData, ops::Deref, rc::Rc};
-use web_sys::AbortSignal;
-use yew::{hook, use_state, Callback, UseStateHandle};
+use std::{cell::RefCell, fmt::Debug, marker::PhantomData, ops::Deref, rc::Rc, time::Duration};
+use web_sys::{AbortController, AbortSignal};
+use yew::{
+    hook, use_context, use_mut_ref, use_reducer, use_state, Callback, Reducible, UseReducerHandle,
+    UseStateHandle,
+};
 
 /// Additional options to set to a client request.
 #[derive(Debug, Clone)]
 pub struct RequestOptions {
     pub headers: HeaderMap,
     pub method: Method,
+
+    /// The policy to retry the request, used by `use_action`.
+    pub retry: Option<RetryPolicy>,
+
+    /// The max time to wait for each attempt of the request, used by `use_action`.
+    pub timeout: Option<Duration>,
 }
 
 impl RequestOptions {
@@ -18,6 +27,8 @@ impl RequestOptions {
         RequestOptions {
             headers: HeaderMap::new(),
             method: Method::POST,
+            retry: None,
+            timeout: None,
         }
     }
 
@@ -64,6 +75,18 @@ impl RequestOptions {
         self.method = method;
         self
     }
+
+    /// Sets the policy to retry the request if fails, only idempotent methods are retried by default.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets the max time to wait for each attempt of the request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
 }
 
 impl Default for RequestOptions {
@@ -89,9 +112,37 @@ impl<A: Action> Deref for UseActionRef<A> {
     }
 }
 
+/// The progress of the transfer of a request or response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of bytes transferred.
+    pub loaded: u64,
+
+    /// The total number of bytes, if known.
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// Returns the transferred fraction between `0.0` and `1.0`, if the total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.loaded as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+#[allow(type_alias_bounds)]
+type OptimisticReducer<A: Action> = Rc<dyn Fn(Option<&ActionData<A>>) -> ActionData<A>>;
+
 pub struct UseActionOptions<A: Action> {
     on_complete: Option<Callback<UseActionRef<A>>>,
     signal: Option<AbortSignal>,
+    optimistic: Option<OptimisticReducer<A>>,
+    retry: Option<RetryPolicy>,
+    timeout: Option<Duration>,
+    track_progress: bool,
 }
 
 impl<A: Action> UseActionOptions<A> {
@@ -111,6 +162,43 @@ impl<A: Action> UseActionOptions<A> {
         self.signal = Some(signal);
         self
     }
+
+    /// Sets a function that returns the expected data of the action from the current data.
+    ///
+    /// The value is used as the action data while the request is sent, if the action fails
+    /// the data is rolled back to the last value returned by the server,
+    /// otherwise is replaced by the new value returned by the server.
+    pub fn optimistic<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Option<&ActionData<A>>) -> ActionData<A> + 'static,
+    {
+        self.optimistic = Some(Rc::new(f));
+        self
+    }
+
+    /// Sets the policy to retry the requests, used when the `RequestOptions` don't have one.
+    ///
+    /// Actions are sent with `POST` by default, which is only retried if the policy allows it.
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets the max time to wait for each attempt of the requests,
+    /// used when the `RequestOptions` don't have one.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Reports the upload and download progress of the requests.
+    ///
+    /// The requests are sent using a `XMLHttpRequest` instead of `fetch`,
+    /// because `fetch` doesn't report the upload progress.
+    pub fn track_progress(mut self) -> Self {
+        self.track_progress = true;
+        self
+    }
 }
 
 impl<A: Action> Debug for UseActionOptions<A> {
@@ -118,6 +206,10 @@ impl<A: Action> Debug for UseActionOptions<A> {
         f.debug_struct("UseActionOptions")
             .field("on_complete", &self.on_complete)
             .field("signal", &self.signal)
+            .field("optimistic", &self.optimistic.is_some())
+            .field("retry", &self.retry)
+            .field("timeout", &self.timeout)
+            .field("track_progress", &self.track_progress)
             .finish()
     }
 }
@@ -127,6 +219,10 @@ impl<A: Action> Clone for UseActionOptions<A> {
         Self {
             on_complete: self.on_complete.clone(),
             signal: self.signal.clone(),
+            optimistic: self.optimistic.clone(),
+            retry: self.retry.clone(),
+            timeout: self.timeout,
+            track_progress: self.track_progress,
         }
     }
 }
@@ -136,6 +232,81 @@ impl<A: Action> Default for UseActionOptions<A> {
         Self {
             on_complete: Default::default(),
             signal: Default::default(),
+            optimistic: Default::default(),
+            retry: Default::default(),
+            timeout: Default::default(),
+            track_progress: Default::default(),
+        }
+    }
+}
+
+/// The results of the requests of an action.
+#[derive(Debug)]
+struct ActionState<D> {
+    // The result of the last completed request
+    result: Option<Rc<crate::Result<D>>>,
+
+    // The result of the last successful request, the data is kept if the next request fails
+    last_ok: Option<Rc<crate::Result<D>>>,
+
+    // The value used as the data while the request is processing
+    optimistic: Option<Rc<D>>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+enum ActionStateUpdate<D> {
+    // A request was sent with the given optimistic value
+    Send(Option<Rc<D>>),
+
+    // The last request sent completed
+    Complete(Rc<crate::Result<D>>),
+}
+
+impl<D> ActionState<D> {
+    fn new(result: Option<Rc<crate::Result<D>>>) -> Self {
+        let last_ok = result.clone().filter(|x| x.is_ok());
+        ActionState {
+            result,
+            last_ok,
+            optimistic: None,
+        }
+    }
+
+    fn data(&self) -> Option<&D> {
+        if let Some(data) = self.optimistic.as_deref() {
+            return Some(data);
+        }
+
+        self.last_ok.as_deref().and_then(|x| x.as_ref().ok())
+    }
+
+    fn error(&self) -> Option<&BoxError> {
+        self.result.as_deref().and_then(|x| x.as_ref().err())
+    }
+}
+
+impl<D: 'static> Reducible for ActionState<D> {
+    type Action = ActionStateUpdate<D>;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            ActionStateUpdate::Send(optimistic) => Rc::new(ActionState {
+                result: self.result.clone(),
+                last_ok: self.last_ok.clone(),
+                optimistic: optimistic.or_else(|| self.optimistic.clone()),
+            }),
+            ActionStateUpdate::Complete(result) => {
+                let last_ok = match result.is_ok() {
+                    true => Some(result.clone()),
+                    false => self.last_ok.clone(),
+                };
+
+                Rc::new(ActionState {
+                    result: Some(result),
+                    last_ok,
+                    optimistic: None,
+                })
+            }
         }
     }
 }
@@ -146,7 +317,13 @@ where
     A: Action,
 {
     loading: UseStateHandle<bool>,
-    result: UseStateHandle<Option<Rc<ActionResult<A>>>>,
+    state: UseReducerHandle<ActionState<ActionData<A>>>,
+    // Number of the last request sent, only the last request updates the state
+    last_request: Rc<RefCell<usize>>,
+    upload_progress: UseStateHandle<Option<Progress>>,
+    download_progress: UseStateHandle<Option<Progress>>,
+    attempts: UseStateHandle<u32>,
+    abort_controller: Rc<RefCell<Option<AbortController>>>,
     options: UseActionOptions<A>,
     _marker: PhantomData<T>,
 }
@@ -163,20 +340,72 @@ where
 
     /// Returns `true` if the action returned an error.
     pub fn is_error(&self) -> bool {
-        match &*self.result {
-            Some(x) => x.is_err(),
-            None => false,
-        }
+        self.state.error().is_some()
+    }
+
+    /// Returns `true` if the action is processing and the data is the optimistic value.
+    pub fn is_optimistic(&self) -> bool {
+        self.state.optimistic.is_some()
     }
 
-    /// Returns the response value if any.
+    /// Returns the last value returned by the server, if any.
+    ///
+    /// While the action is processing this returns the optimistic value, if any.
+    /// If the last request failed this still returns the value of the last successful request.
     pub fn data(&self) -> Option<&<A::Response as IntoJsonResponse>::Data> {
-        self.result.as_deref().and_then(|x| x.as_ref().ok())
+        self.state.data()
     }
 
-    /// Returns the response error if any.
+    /// Returns the error of the last request, if failed.
     pub fn error(&self) -> Option<&BoxError> {
-        self.result.as_deref().and_then(|x| x.as_ref().err())
+        self.state.error()
+    }
+
+    /// Returns the errors of the fields of the input, if the action rejected it.
+    pub fn field_errors(&self) -> Option<&ValidationErrors> {
+        self.error()?.downcast_ref()
+    }
+
+    /// Returns the progress of the upload of the request body, if the action is processing.
+    ///
+    /// The progress is only reported if enabled with `UseActionOptions::track_progress`.
+    pub fn upload_progress(&self) -> Option<Progress> {
+        *self.upload_progress
+    }
+
+    /// Returns the progress of the download of the response, if the action is processing.
+    ///
+    /// The progress is only reported if enabled with `UseActionOptions::track_progress`.
+    pub fn download_progress(&self) -> Option<Progress> {
+        *self.download_progress
+    }
+
+    /// Returns the number of times the last request was sent, including the retries.
+    pub fn attempts(&self) -> u32 {
+        *self.attempts
+    }
+
+    /// Returns `true` if the action is processing and the request failed at least once.
+    pub fn is_retrying(&self) -> bool {
+        *self.loading && *self.attempts > 1
+    }
+
+    /// Aborts the request in progress, if any.
+    ///
+    /// The action completes with an error.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cancel(&self) {
+        unreachable!("client only function")
+    }
+
+    /// Aborts the request in progress, if any.
+    ///
+    /// The action completes with an error.
+    #[cfg(target_arch = "wasm32")]
+    pub fn cancel(&self) {
+        if let Some(controller) = self.abort_controller.borrow_mut().take() {
+            controller.abort();
+        }
     }
 
     /// Sends a request to the server.
@@ -202,11 +431,37 @@ where
 
     #[cfg(target_arch = "wasm32")]
     pub fn send_with_options(&self, obj: T, options: RequestOptions) -> Result<(), BoxError> {
-        use crate::actions::into_request_config::RequestInitConfig;
-        use crate::utils::wasm::fetch_json;
+        let optimistic = self.options.optimistic.as_ref().map(|f| f(self.data()));
+        self.send_request(obj, options, optimistic)
+    }
+
+    /// Sends a request to the server using the given value as the data until the action completes.
+    ///
+    /// If the action fails the data is rolled back, otherwise is replaced by the value returned by the server.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused_variables)]
+    pub fn send_optimistic(&self, obj: T, value: ActionData<A>) -> Result<(), BoxError> {
+        unreachable!("client only function")
+    }
+
+    /// Sends a request to the server using the given value as the data until the action completes.
+    ///
+    /// If the action fails the data is rolled back, otherwise is replaced by the value returned by the server.
+    #[cfg(target_arch = "wasm32")]
+    pub fn send_optimistic(&self, obj: T, value: ActionData<A>) -> Result<(), BoxError> {
+        self.send_request(obj, RequestOptions::new(), Some(value))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn send_request(
+        &self,
+        obj: T,
+        options: RequestOptions,
+        optimistic: Option<ActionData<A>>,
+    ) -> Result<(), BoxError> {
+        use super::create_action_request;
         use crate::error::JsError;
-        use wasm_bindgen::{JsCast, JsValue};
-        use web_sys::{Headers, RequestInit};
+        use wasm_bindgen::{closure::Closure, JsCast};
 
         struct OnDrop<F: FnOnce()>(Option<F>);
         impl<F: FnOnce()> Drop for OnDrop<F> {
@@ -217,81 +472,209 @@ where
             }
         }
 
-        let loading = self.loading.clone();
-        loading.set(true);
+        // Removes the listener from the user signal when the request completes
+        struct AbortListener {
+            signal: AbortSignal,
+            callback: Closure<dyn FnMut()>,
+        }
 
-        let _guard = OnDrop(Some(move || loading.set(false)));
-        let result = self.result.clone();
-
-        let request_config = obj.into_request_config(&options)?;
-        let RequestInitConfig {
-            init,
-            search_params,
-        } = request_config;
-
-        let mut init = init.unwrap_or_else(|| RequestInit::new());
-        let headers = match js_sys::Reflect::get(&init, &JsValue::from("headers")) {
-            Ok(x) => {
-                // If is falsy means it can be null or undefined, so we just create an instance
-                if x.is_falsy() {
-                    Headers::new().map_err(JsError::new)?
-                } else {
-                    // Otherwise we try to convert and return a new header if fail
-                    match x.dyn_into::<Headers>() {
-                        Ok(headers) => headers,
-                        Err(err) => {
-                            log::debug!("failed to cast property `headers` to Headers type: {err:?}");
-                            Headers::new().map_err(JsError::new)?
-                        },
-                    }
-                }
+        impl Drop for AbortListener {
+            fn drop(&mut self) {
+                let _ = self.signal.remove_event_listener_with_callback(
+                    "abort",
+                    self.callback.as_ref().unchecked_ref(),
+                );
             }
-            Err(err) => {
-                log::debug!("failed to get `RequestInit::headers`: {err:?}");
-                Headers::new().map_err(JsError::new)?
+        }
+
+        let retry = options.retry.clone().or_else(|| self.options.retry.clone());
+        let timeout = options.timeout.or(self.options.timeout);
+        let track_progress = self.options.track_progress;
+        let request = create_action_request::<A, T>(obj, options)?;
+        let controller = AbortController::new().map_err(JsError::new)?;
+
+        // Forward the abort of the user signal to the request
+        let abort_listener = match &self.options.signal {
+            Some(signal) => {
+                if signal.aborted() {
+                    controller.abort();
+                }
+
+                let callback = {
+                    let controller = controller.clone();
+                    Closure::<dyn FnMut()>::new(move || controller.abort())
+                };
+
+                signal
+                    .add_event_listener_with_callback("abort", callback.as_ref().unchecked_ref())
+                    .map_err(JsError::new)?;
+
+                Some(AbortListener {
+                    signal: signal.clone(),
+                    callback,
+                })
             }
+            None => None,
         };
 
-        let mut last_name = None;
-        for (name, value) in options.headers {
-            if let Some(name) = name {
-                last_name = Some(name);
-            }
+        *self.abort_controller.borrow_mut() = Some(controller.clone());
 
-            let key = last_name.as_ref().unwrap();
-            headers
-                .append(key.as_str(), value.to_str()?)
-                .map_err(JsError::new)?;
-        }
+        let request_id = {
+            let mut last_request = self.last_request.borrow_mut();
+            *last_request += 1;
+            *last_request
+        };
 
-        init.headers(&headers);
-        init.method(options.method.as_str());
-        init.signal(self.options.signal.as_ref());
+        let loading = self.loading.clone();
+        let upload_progress = self.upload_progress.clone();
+        let download_progress = self.download_progress.clone();
+        loading.set(true);
+        upload_progress.set(None);
+        download_progress.set(None);
+
+        // Only the last request sent ends the loading
+        let _guard = OnDrop(Some({
+            let upload_progress = upload_progress.clone();
+            let download_progress = download_progress.clone();
+            let last_request = self.last_request.clone();
+            move || {
+                if *last_request.borrow() == request_id {
+                    loading.set(false);
+                    upload_progress.set(None);
+                    download_progress.set(None);
+                }
+            }
+        }));
 
-        let mut url = A::route().to_owned();
+        let on_complete = self.options.on_complete.clone();
 
-        if let Some(search_params) = search_params {
-            url.push_str(&format!("?{search}", search = search_params.to_string()));
-        }
+        self.state
+            .dispatch(ActionStateUpdate::Send(optimistic.map(Rc::new)));
 
-        let request = web_sys::Request::new_with_str_and_init(&url, &init).map_err(JsError::new)?;
-        let on_complete = self.options.on_complete.clone();
+        let state = self.state.clone();
+        let last_request = self.last_request.clone();
+        let abort_controller = self.abort_controller.clone();
+        let attempts = self.attempts.clone();
 
         wasm_bindgen_futures::spawn_local(async move {
             let _guard = _guard;
-            let ret = Rc::new(fetch_json(request).await);
+            let _abort_listener = abort_listener;
+            let ret = fetch_with_retry(
+                request,
+                &controller.signal(),
+                retry.unwrap_or_else(|| RetryPolicy::new(1)),
+                timeout,
+                attempts,
+                track_progress.then_some((upload_progress, download_progress)),
+            )
+            .await;
+
+            let ret = Rc::new(ret);
 
             if let Some(on_complete) = on_complete {
                 on_complete.emit(UseActionRef(ret.clone()));
             }
 
-            result.set(Some(ret));
+            // A newer request is still waiting, so this result is outdated
+            if *last_request.borrow() != request_id {
+                return;
+            }
+
+            abort_controller.borrow_mut().take();
+            state.dispatch(ActionStateUpdate::Complete(ret));
         });
 
         Ok(())
     }
 }
 
+// The state of the upload or download progress of a request.
+#[cfg(target_arch = "wasm32")]
+type ProgressHandle = UseStateHandle<Option<Progress>>;
+
+/// Sends the request until succeeds or there are no attempts left, and returns the json response.
+#[cfg(target_arch = "wasm32")]
+async fn fetch_with_retry<S>(
+    request: web_sys::Request,
+    signal: &AbortSignal,
+    retry: RetryPolicy,
+    timeout: Option<Duration>,
+    attempts: UseStateHandle<u32>,
+    progress: Option<(ProgressHandle, ProgressHandle)>,
+) -> Result<S, BoxError>
+where
+    S: serde::de::DeserializeOwned,
+{
+    use crate::{
+        error::JsError,
+        utils::wasm::{read_json_response, send_with_fetch, send_with_progress, sleep, SendError},
+    };
+    use http::StatusCode;
+
+    // Requests that are not idempotent are never retried, the server may already processed them
+    let is_retryable_method = request
+        .method()
+        .parse::<Method>()
+        .map(|method| retry.is_retryable_method(&method))
+        .unwrap_or(false);
+
+    let mut attempt = 1;
+
+    loop {
+        attempts.set(attempt);
+
+        // The body of a request can only be read once
+        let attempt_request = web_sys::Request::clone(&request).map_err(JsError::new)?;
+        // `fetch` is used unless the progress is reported, which requires a `XMLHttpRequest`
+        let ret = match &progress {
+            Some((upload_progress, download_progress)) => {
+                send_with_progress(
+                    &attempt_request,
+                    Some(signal),
+                    timeout,
+                    {
+                        let upload_progress = upload_progress.clone();
+                        move |progress| upload_progress.set(Some(progress))
+                    },
+                    {
+                        let download_progress = download_progress.clone();
+                        move |progress| download_progress.set(Some(progress))
+                    },
+                )
+                .await
+            }
+            None => send_with_fetch(&attempt_request, Some(signal), timeout).await,
+        };
+
+        let is_retryable = is_retryable_method
+            && match &ret {
+                Ok(resp) => StatusCode::from_u16(resp.status())
+                    .map(|status| retry.is_retryable(status))
+                    .unwrap_or(false),
+                Err(SendError::TimedOut | SendError::Network) => true,
+                Err(_) => false,
+            };
+
+        match retry.delay_for(attempt + 1) {
+            Some(delay) if is_retryable => {
+                sleep(delay).await;
+
+                if signal.aborted() {
+                    return Err(SendError::Cancelled.into());
+                }
+
+                attempt += 1;
+            }
+            _ => {
+                return match ret {
+                    Ok(resp) => read_json_response(resp).await,
+                    Err(err) => Err(err.into()),
+                };
+            }
+        }
+    }
+}
+
 impl<A, T> Clone for UseActionHandle<A, T>
 where
     A: Action,
@@ -299,7 +682,12 @@ where
     fn clone(&self) -> Self {
         Self {
             loading: self.loading.clone(),
-            result: self.result.clone(),
+            state: self.state.clone(),
+            last_request: self.last_request.clone(),
+            upload_progress: self.upload_progress.clone(),
+            download_progress: self.download_progress.clone(),
+            attempts: self.attempts.clone(),
+            abort_controller: self.abort_controller.clone(),
             options: self.options.clone(),
             _marker: self._marker,
         }
@@ -312,7 +700,11 @@ where
 {
     fn eq(&self, other: &Self) -> bool {
         // TODO: Add proper equality implementation
-        self.loading == other.loading && std::ptr::eq(&*self.result, &*other.result)
+        self.loading == other.loading
+            && std::ptr::eq(&*self.state, &*other.state)
+            && self.upload_progress == other.upload_progress
+            && self.download_progress == other.download_progress
+            && self.attempts == other.attempts
     }
 }
 
@@ -324,7 +716,10 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UseActionHandle")
             .field("loading", &self.loading)
-            .field("result", &self.result)
+            .field("state", &self.state)
+            .field("upload_progress", &self.upload_progress)
+            .field("download_progress", &self.download_progress)
+            .field("attempts", &self.attempts)
             .field("options", &self.options)
             .finish()
     }
@@ -361,13 +756,63 @@ where
     A: Action,
     T: IntoRequestConfig,
 {
-    let result = use_state(|| None);
+    // The result of the action sent by a native form submission, if any
+    let page_data = use_context::<PageDataHandle>();
+    let state = use_reducer(|| {
+        let result = page_data
+            .as_ref()
+            .and_then(|page_data| page_data.action_flash.as_ref())
+            .and_then(|flash| flash.result_for::<A>())
+            .map(Rc::new);
+
+        ActionState::new(result)
+    });
     let loading = use_state(|| false);
+    let last_request = use_mut_ref(|| 0);
+    let upload_progress = use_state(|| None);
+    let download_progress = use_state(|| None);
+    let attempts = use_state(|| 0);
+    let abort_controller = use_mut_ref(|| None);
 
     UseActionHandle {
-        result,
+        state,
         loading,
+        last_request,
+        upload_progress,
+        download_progress,
+        attempts,
+        abort_controller,
         options,
         _marker: PhantomData,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionState, ActionStateUpdate};
+    use std::rc::Rc;
+    use yew::Reducible;
+
+    #[test]
+    fn action_state_optimistic_test() {
+        let state = Rc::new(ActionState::<u32>::new(None));
+        assert_eq!(state.data(), None);
+
+        // The optimistic value is used while the request is processing
+        let state = state.reduce(ActionStateUpdate::Send(Some(Rc::new(1))));
+        assert_eq!(state.data(), Some(&1));
+
+        // Is replaced by the value returned by the server
+        let state = state.reduce(ActionStateUpdate::Complete(Rc::new(Ok(2))));
+        assert_eq!(state.data(), Some(&2));
+        assert!(state.error().is_none());
+
+        // And rolled back to the last returned value if the request fails
+        let state = state.reduce(ActionStateUpdate::Send(Some(Rc::new(3))));
+        assert_eq!(state.data(), Some(&3));
+
+        let state = state.reduce(ActionStateUpdate::Complete(Rc::new(Err("failed".into()))));
+        assert_eq!(state.data(), Some(&2));
+        assert!(state.error().is_some());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::{BoxError, JsError},
-    web::{Form, Json},
+    web::{Form, Json, Query, Valid},
 };
 use http::{
     header::{self},
@@ -145,6 +145,30 @@ impl<T: Serialize> IntoRequestConfig for Form<T> {
     }
 }
 
+impl<T: Serialize> IntoRequestConfig for Query<T> {
+    fn into_request_config(self, _options: &RequestOptions) -> Result<RequestInitConfig, BoxError> {
+        let query = serde_qs::to_string(&self.0)?;
+        let search_params = UrlSearchParams::new_with_str(&query).map_err(JsError::new)?;
+
+        Ok(RequestInitConfig {
+            init: None,
+            search_params: Some(search_params),
+        })
+    }
+}
+
+impl<T: IntoRequestConfig> IntoRequestConfig for Valid<T> {
+    fn into_request_config(self, options: &RequestOptions) -> Result<RequestInitConfig, BoxError> {
+        self.0.into_request_config(options)
+    }
+}
+
+impl IntoRequestConfig for () {
+    fn into_request_config(self, _options: &RequestOptions) -> Result<RequestInitConfig, BoxError> {
+        Ok(RequestInitConfig::default())
+    }
+}
+
 impl IntoRequestConfig for FormData {
     fn into_request_config(self, _options: &RequestOptions) -> Result<RequestInitConfig, BoxError> {
         let mut init = RequestInit::new();
@@ -1,17 +1,35 @@
 mod any_form;
+mod csrf;
+mod fetch;
+mod flash;
+mod guard;
 mod handler;
 mod hooks;
+mod query;
+mod retry;
+mod stream;
+mod validation;
 pub mod into_request_config;
 
 use crate::{
     app::RequestContext,
+    error::ServerError,
     routing::RouteMethod,
     types::BoxFuture,
     web::{Body, IntoJsonResponse},
 };
 pub use any_form::*;
+pub use csrf::*;
+pub use fetch::*;
+pub use flash::*;
+pub use guard::*;
 pub use handler::*;
 pub use hooks::*;
+pub use query::*;
+pub use retry::*;
+pub use stream::*;
+pub use validation::*;
+pub use hashira_macros::Validate;
 
 /// An action that can be execute on the server.
 pub trait Action: 'static {
@@ -30,6 +48,16 @@ pub trait Action: 'static {
             | RouteMethod::DELETE
     }
 
+    /// Returns `true` if the requests to this action must include the CSRF token.
+    fn csrf_protection() -> bool {
+        true
+    }
+
+    /// Checks the request before the action is called, if fails the action is not called.
+    fn guard(_ctx: RequestContext) -> BoxFuture<Result<(), ServerError>> {
+        Box::pin(async { Ok(()) })
+    }
+
     /// Call this action and returns a response.
     fn call(ctx: RequestContext, body: Body) -> BoxFuture<crate::Result<Self::Response>>;
 }
@@ -0,0 +1,582 @@
+use super::Action;
+use crate::{context::PageDataHandle, error::BoxError, web::IntoJsonResponse};
+use serde::Serialize;
+use std::{fmt::Debug, rc::Rc, time::Duration};
+use yew::{hook, use_context, Callback};
+
+#[allow(type_alias_bounds)]
+type ActionData<A: Action> = <A::Response as IntoJsonResponse>::Data;
+
+/// Returns the key used to cache the data of the given action and input.
+///
+/// The input is sent as the query string of the request, inputs that cannot be
+/// serialized as a query string like `()` are sent without query string.
+pub fn query_key<A, Q>(input: &Q) -> String
+where
+    A: Action,
+    Q: Serialize,
+{
+    match get_query_string(input) {
+        Some(query) => format!("{}?{query}", A::route()),
+        None => A::route().to_owned(),
+    }
+}
+
+fn get_query_string<Q: Serialize>(input: &Q) -> Option<String> {
+    serde_qs::to_string(input).ok().filter(|x| !x.is_empty())
+}
+
+/// Options for `use_query`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseQueryOptions {
+    stale_time: Duration,
+    gc_time: Duration,
+    refetch_on_window_focus: bool,
+    refetch_interval: Option<Duration>,
+    enabled: bool,
+}
+
+impl UseQueryOptions {
+    /// Constructs a default instance.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the time the data is considered fresh, fresh data is not fetched again
+    /// when other component use the query. Defaults to zero.
+    pub fn stale_time(mut self, stale_time: Duration) -> Self {
+        self.stale_time = stale_time;
+        self
+    }
+
+    /// Sets the time the data is kept in the cache after the last component using the query
+    /// is unmounted, after that the data is removed. Defaults to 5 minutes.
+    pub fn gc_time(mut self, gc_time: Duration) -> Self {
+        self.gc_time = gc_time;
+        self
+    }
+
+    /// Whether if fetch the stale data when the window gets the focus. Defaults to `true`.
+    pub fn refetch_on_window_focus(mut self, refetch: bool) -> Self {
+        self.refetch_on_window_focus = refetch;
+        self
+    }
+
+    /// Fetches the data each time the given interval elapses.
+    pub fn refetch_interval(mut self, interval: Duration) -> Self {
+        self.refetch_interval = Some(interval);
+        self
+    }
+
+    /// Whether if the query fetches the data automatically. Defaults to `true`.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl Default for UseQueryOptions {
+    fn default() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            gc_time: Duration::from_secs(5 * 60),
+            refetch_on_window_focus: true,
+            refetch_interval: None,
+            enabled: true,
+        }
+    }
+}
+
+/// A handle to the data of a query.
+pub struct UseQueryHandle<A>
+where
+    A: Action,
+{
+    data: Option<Rc<ActionData<A>>>,
+    error: Option<Rc<BoxError>>,
+    fetching: bool,
+    refetch: Callback<()>,
+}
+
+impl<A> UseQueryHandle<A>
+where
+    A: Action,
+{
+    /// Returns the data of the query, if any.
+    ///
+    /// While the query is fetching this returns the previous data.
+    pub fn data(&self) -> Option<&ActionData<A>> {
+        self.data.as_deref()
+    }
+
+    /// Returns the error of the last fetch, if any.
+    pub fn error(&self) -> Option<&BoxError> {
+        self.error.as_deref()
+    }
+
+    /// Returns `true` if the query is fetching and there is no data yet.
+    pub fn is_loading(&self) -> bool {
+        self.fetching && self.data.is_none()
+    }
+
+    /// Returns `true` if the query is fetching.
+    pub fn is_fetching(&self) -> bool {
+        self.fetching
+    }
+
+    /// Fetches the data of the query, if is not already fetching.
+    pub fn refetch(&self) {
+        self.refetch.emit(());
+    }
+}
+
+impl<A> Clone for UseQueryHandle<A>
+where
+    A: Action,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            error: self.error.clone(),
+            fetching: self.fetching,
+            refetch: self.refetch.clone(),
+        }
+    }
+}
+
+impl<A> PartialEq for UseQueryHandle<A>
+where
+    A: Action,
+{
+    fn eq(&self, other: &Self) -> bool {
+        fn ptr_eq<T: ?Sized>(a: &Option<Rc<T>>, b: &Option<Rc<T>>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+
+        self.fetching == other.fetching
+            && ptr_eq(&self.data, &other.data)
+            && ptr_eq(&self.error, &other.error)
+    }
+}
+
+impl<A> Debug for UseQueryHandle<A>
+where
+    A: Action,
+    ActionData<A>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseQueryHandle")
+            .field("data", &self.data)
+            .field("error", &self.error)
+            .field("fetching", &self.fetching)
+            .finish()
+    }
+}
+
+/// Returns the data of a `GET` action using the given input as query string.
+///
+/// The data is cached and shared with all the components using the same action and input,
+/// and only one request is sent at the time for each query.
+/// The data added to the page with `RenderContext::query_data` is used without fetching it again.
+///
+/// # Example
+/// ```rust,ignore
+/// #[action]
+/// async fn GetTodos(filter: Query<TodoFilter>) -> Json<Vec<Todo>> {
+///     todo!()
+/// }
+///
+/// #[page_component("/todos")]
+/// fn TodosPage() -> yew::Html {
+///     let todos = use_query::<GetTodos, _>(TodoFilter { done: false });
+///
+///     match todos.data() {
+///         Some(todos) => html! { <TodoList todos={todos.clone()} /> },
+///         None => html! { "Loading..." },
+///     }
+/// }
+/// ```
+#[hook]
+pub fn use_query<A, Q>(input: Q) -> UseQueryHandle<A>
+where
+    A: Action,
+    ActionData<A>: 'static,
+    Q: Serialize + 'static,
+{
+    use_query_with_options::<A, Q>(input, UseQueryOptions::default())
+}
+
+/// Returns the data of a `GET` action using the given input as query string and options.
+#[hook]
+pub fn use_query_with_options<A, Q>(input: Q, options: UseQueryOptions) -> UseQueryHandle<A>
+where
+    A: Action,
+    ActionData<A>: 'static,
+    Q: Serialize + 'static,
+{
+    let key = query_key::<A, Q>(&input);
+    let page_data = use_context::<PageDataHandle>();
+    let seed = page_data
+        .as_ref()
+        .and_then(|x| x.queries.get(&key))
+        .cloned();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = options;
+
+        // On the server we only have the data added to the page
+        let data = seed
+            .and_then(|value| serde_json::from_value::<ActionData<A>>(value).ok())
+            .map(Rc::new);
+
+        UseQueryHandle {
+            data,
+            error: None,
+            fetching: false,
+            refetch: Callback::noop(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use yew::{use_effect_with_deps, use_force_update};
+
+        let query = get_query_string(&input);
+        let force_update = use_force_update();
+
+        if let Some(value) = seed {
+            cache::seed::<ActionData<A>>(&key, value);
+        }
+
+        let (data, error, fetching) = cache::with_entry(&key, |entry| {
+            let data = entry.data.clone().and_then(|x| x.downcast().ok());
+            (data, entry.error.clone(), entry.fetching)
+        });
+
+        let refetch = {
+            let key = key.clone();
+            let query = query.clone();
+            Callback::from(move |_| cache::fetch::<A>(key.clone(), query.clone()))
+        };
+
+        use_effect_with_deps(
+            move |(key, query, options)| {
+                let subscription = cache::subscribe(
+                    key.clone(),
+                    options.gc_time,
+                    Callback::from(move |_| force_update.force_update()),
+                );
+
+                let listeners = match options.enabled {
+                    true => Some(cache::listen::<A>(key.clone(), query.clone(), options)),
+                    false => None,
+                };
+
+                move || {
+                    drop(listeners);
+                    drop(subscription);
+                }
+            },
+            (key, query, options),
+        );
+
+        UseQueryHandle {
+            data,
+            error,
+            fetching,
+            refetch,
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod cache {
+    use super::{Action, ActionData, UseQueryOptions};
+    use crate::{
+        actions::{fetch_action, into_request_config::*, RequestOptions},
+        error::{BoxError, JsError},
+    };
+    use std::{
+        any::Any,
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        rc::Rc,
+        time::Duration,
+    };
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::UrlSearchParams;
+    use yew::Callback;
+
+    #[derive(Default)]
+    pub struct QueryEntry {
+        pub data: Option<Rc<dyn Any>>,
+        pub error: Option<Rc<BoxError>>,
+        pub fetching: bool,
+        // Data added by the server is not fetched again when the page is hydrated
+        seeded: bool,
+        updated_at: f64,
+        subscribers: Vec<(usize, Callback<()>)>,
+        // When the last subscriber was removed and the time to keep the data after that
+        unused_since: Option<(f64, Duration)>,
+    }
+
+    thread_local! {
+        static QUERY_CACHE: RefCell<HashMap<String, QueryEntry>> = Default::default();
+        static NEXT_SUBSCRIBER_ID: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Calls the given function with the entry of the query.
+    pub fn with_entry<R>(key: &str, f: impl FnOnce(&mut QueryEntry) -> R) -> R {
+        QUERY_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let entry = cache.entry(key.to_owned()).or_default();
+            f(entry)
+        })
+    }
+
+    /// Adds the data of the page to the cache, if there is no data for the query.
+    pub fn seed<T: serde::de::DeserializeOwned + 'static>(key: &str, value: serde_json::Value) {
+        with_entry(key, |entry| {
+            if entry.data.is_some() {
+                return;
+            }
+
+            if let Ok(data) = serde_json::from_value::<T>(value) {
+                entry.data = Some(Rc::new(data));
+                entry.seeded = true;
+                entry.updated_at = js_sys::Date::now();
+            }
+        });
+    }
+
+    fn notify(key: &str) {
+        // Callbacks are called after the cache is released
+        let subscribers = with_entry(key, |entry| {
+            entry
+                .subscribers
+                .iter()
+                .map(|(_, callback)| callback.clone())
+                .collect::<Vec<_>>()
+        });
+
+        for callback in subscribers {
+            callback.emit(());
+        }
+    }
+
+    /// Removes the subscriber on drop.
+    pub struct Subscription {
+        key: String,
+        id: usize,
+        gc_time: Duration,
+    }
+
+    impl Drop for Subscription {
+        fn drop(&mut self) {
+            let is_unused = with_entry(&self.key, |entry| {
+                entry.subscribers.retain(|(id, _)| *id != self.id);
+
+                if entry.subscribers.is_empty() {
+                    entry.unused_since = Some((js_sys::Date::now(), self.gc_time));
+                }
+
+                entry.subscribers.is_empty()
+            });
+
+            if is_unused {
+                schedule_gc(self.key.clone(), self.gc_time);
+            }
+        }
+    }
+
+    /// Calls the callback each time the query changes,
+    /// the data is removed after `gc_time` when there are no subscribers.
+    pub fn subscribe(key: String, gc_time: Duration, callback: Callback<()>) -> Subscription {
+        let id = NEXT_SUBSCRIBER_ID.with(|x| {
+            let id = x.get();
+            x.set(id + 1);
+            id
+        });
+
+        with_entry(&key, |entry| {
+            entry.subscribers.push((id, callback));
+            entry.unused_since = None;
+        });
+
+        Subscription { key, id, gc_time }
+    }
+
+    // Removes the query after the given time if is still unused
+    fn schedule_gc(key: String, gc_time: Duration) {
+        let remove = Closure::once_into_js(move || remove_unused(&key));
+        let _ = web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                remove.unchecked_ref(),
+                gc_time.as_millis().min(i32::MAX as u128) as i32,
+            );
+    }
+
+    fn remove_unused(key: &str) {
+        QUERY_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let is_unused = cache.get(key).is_some_and(|entry| {
+                let Some((since, gc_time)) = entry.unused_since else {
+                    return false;
+                };
+
+                // A fetch in progress schedules the removal again when completes
+                let elapsed = js_sys::Date::now() - since;
+                !entry.fetching && elapsed >= gc_time.as_millis() as f64
+            });
+
+            if is_unused {
+                cache.remove(key);
+            }
+        });
+    }
+
+    fn is_stale(key: &str, options: &UseQueryOptions) -> bool {
+        with_entry(key, |entry| {
+            let elapsed = js_sys::Date::now() - entry.updated_at;
+            entry.data.is_none() || elapsed >= options.stale_time.as_millis() as f64
+        })
+    }
+
+    /// Fetches the data of the query, if is not already fetching.
+    pub fn fetch<A>(key: String, query: Option<String>)
+    where
+        A: Action,
+        ActionData<A>: 'static,
+    {
+        let can_fetch = with_entry(&key, |entry| !std::mem::replace(&mut entry.fetching, true));
+
+        if !can_fetch {
+            return;
+        }
+
+        notify(&key);
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let options = RequestOptions::new().method(http::Method::GET);
+            let ret = fetch_action::<A, QueryString>(QueryString(query), options).await;
+
+            let unused = with_entry(&key, |entry| {
+                entry.fetching = false;
+                entry.seeded = false;
+                entry.updated_at = js_sys::Date::now();
+
+                match ret {
+                    Ok(data) => {
+                        entry.data = Some(Rc::new(data));
+                        entry.error = None;
+                    }
+                    Err(err) => {
+                        entry.error = Some(Rc::new(err));
+                    }
+                }
+
+                // All the components were unmounted while fetching
+                if let Some((_, gc_time)) = entry.unused_since {
+                    entry.unused_since = Some((js_sys::Date::now(), gc_time));
+                }
+
+                entry.unused_since
+            });
+
+            if let Some((_, gc_time)) = unused {
+                schedule_gc(key.clone(), gc_time);
+            }
+
+            notify(&key);
+        });
+    }
+
+    /// Fetches the query when mounted, on window focus and on intervals, until dropped.
+    pub struct Listeners {
+        focus: Option<Closure<dyn Fn()>>,
+        interval: Option<(i32, Closure<dyn Fn()>)>,
+    }
+
+    impl Drop for Listeners {
+        fn drop(&mut self) {
+            let window = web_sys::window().unwrap();
+
+            if let Some(focus) = self.focus.take() {
+                let _ = window
+                    .remove_event_listener_with_callback("focus", focus.as_ref().unchecked_ref());
+            }
+
+            if let Some((handle, _)) = self.interval.take() {
+                window.clear_interval_with_handle(handle);
+            }
+        }
+    }
+
+    pub fn listen<A>(key: String, query: Option<String>, options: &UseQueryOptions) -> Listeners
+    where
+        A: Action,
+        ActionData<A>: 'static,
+    {
+        let window = web_sys::window().unwrap();
+        let seeded = with_entry(&key, |entry| entry.seeded);
+
+        if !seeded && is_stale(&key, options) {
+            fetch::<A>(key.clone(), query.clone());
+        }
+
+        let focus = options.refetch_on_window_focus.then(|| {
+            let key = key.clone();
+            let query = query.clone();
+            let options = options.clone();
+            let focus = Closure::<dyn Fn()>::new(move || {
+                if is_stale(&key, &options) {
+                    fetch::<A>(key.clone(), query.clone());
+                }
+            });
+
+            let _ =
+                window.add_event_listener_with_callback("focus", focus.as_ref().unchecked_ref());
+            focus
+        });
+
+        let interval = options.refetch_interval.and_then(|interval| {
+            let tick = Closure::<dyn Fn()>::new(move || fetch::<A>(key.clone(), query.clone()));
+            let handle = window
+                .set_interval_with_callback_and_timeout_and_arguments_0(
+                    tick.as_ref().unchecked_ref(),
+                    interval.as_millis() as i32,
+                )
+                .ok()?;
+
+            Some((handle, tick))
+        });
+
+        Listeners { focus, interval }
+    }
+
+    // The input of the query already serialized
+    struct QueryString(Option<String>);
+
+    impl IntoRequestConfig for QueryString {
+        fn into_request_config(
+            self,
+            _options: &RequestOptions,
+        ) -> Result<RequestInitConfig, BoxError> {
+            let search_params = match self.0 {
+                Some(query) => Some(UrlSearchParams::new_with_str(&query).map_err(JsError::new)?),
+                None => None,
+            };
+
+            Ok(RequestInitConfig {
+                init: None,
+                search_params,
+            })
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use http::{Method, StatusCode};
+use std::time::Duration;
+
+/// Policy to retry the failed requests of an action.
+///
+/// The requests are retried after a network error, a timeout or a response with a retryable status,
+/// the delay between each attempt grows exponentially.
+///
+/// Only the requests with an idempotent method are retried, by default `GET`, `HEAD`, `PUT` and `DELETE`,
+/// a failed `POST` may have been already processed by the server, use `retry_methods` to also retry it
+/// if the action is safe to run twice.
+///
+/// # Example
+/// ```rust,ignore
+/// let options = UseActionOptions::new()
+///     .retry(RetryPolicy::new(3).initial_delay(Duration::from_millis(500)))
+///     .timeout(Duration::from_secs(10));
+///
+/// let action = use_action_with_options::<CreateTodo, _>(options);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    statuses: Vec<StatusCode>,
+    methods: Vec<Method>,
+}
+
+impl RetryPolicy {
+    /// Constructs a policy that sends a request at most `max_attempts` times.
+    ///
+    /// By default the first retry waits `200ms`, the delay doubles on each attempt up to `10s`,
+    /// the retryable statuses are `408`, `429`, `500`, `502`, `503` and `504`,
+    /// and the retryable methods are `GET`, `HEAD`, `PUT` and `DELETE`.
+    ///
+    /// # Panic
+    /// If `max_attempts` is zero.
+    pub fn new(max_attempts: u32) -> Self {
+        assert!(max_attempts > 0, "max attempts cannot be zero");
+
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            methods: vec![Method::GET, Method::HEAD, Method::PUT, Method::DELETE],
+        }
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the max delay between attempts.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets the factor the delay is multiplied by on each attempt.
+    ///
+    /// # Panic
+    /// If the multiplier is lower than `1.0`.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        assert!(multiplier >= 1.0, "multiplier cannot be lower than 1.0");
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets the response statuses that are retried.
+    pub fn retry_on<I>(mut self, statuses: I) -> Self
+    where
+        I: IntoIterator<Item = StatusCode>,
+    {
+        self.statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Sets the request methods that are retried.
+    pub fn retry_methods<I>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = Method>,
+    {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Returns the max number of times a request is sent.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns `true` if a response with the given status should be retried.
+    pub fn is_retryable(&self, status: StatusCode) -> bool {
+        self.statuses.contains(&status)
+    }
+
+    /// Returns `true` if a failed request with the given method should be retried.
+    pub fn is_retryable_method(&self, method: &Method) -> bool {
+        self.methods.contains(method)
+    }
+
+    /// Returns the delay before sending the given attempt, or `None` if there are no attempts left.
+    ///
+    /// The first attempt is `1`, which is sent without delay.
+    pub fn delay_for(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt > self.max_attempts {
+            return None;
+        }
+
+        if attempt == 1 {
+            return Some(Duration::ZERO);
+        }
+
+        let factor = self.multiplier.powi(attempt as i32 - 2);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+        Some(Duration::from_secs_f64(
+            delay.min(self.max_delay.as_secs_f64()),
+        ))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use http::{Method, StatusCode};
+    use std::time::Duration;
+
+    #[test]
+    fn retry_delay_test() {
+        let policy = RetryPolicy::new(5)
+            .initial_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500));
+
+        assert_eq!(policy.delay_for(0), None);
+        assert_eq!(policy.delay_for(1), Some(Duration::ZERO));
+        assert_eq!(policy.delay_for(2), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay_for(3), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay_for(4), Some(Duration::from_millis(400)));
+        assert_eq!(policy.delay_for(5), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay_for(6), None);
+    }
+
+    #[test]
+    fn retry_status_test() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!policy.is_retryable(StatusCode::BAD_REQUEST));
+
+        let policy = policy.retry_on([StatusCode::CONFLICT]);
+        assert!(policy.is_retryable(StatusCode::CONFLICT));
+        assert!(!policy.is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[test]
+    fn retry_method_test() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable_method(&Method::GET));
+        assert!(policy.is_retryable_method(&Method::PUT));
+        assert!(!policy.is_retryable_method(&Method::POST));
+
+        let policy = policy.retry_methods([Method::POST]);
+        assert!(policy.is_retryable_method(&Method::POST));
+        assert!(!policy.is_retryable_method(&Method::GET));
+    }
+}
@@ -0,0 +1,230 @@
+use super::{into_request_config::IntoRequestConfig, Action, RequestOptions};
+use crate::{error::BoxError, web::IntoJsonStream};
+use std::{
+    cell::{Ref, RefCell},
+    fmt::Debug,
+    marker::PhantomData,
+    rc::Rc,
+};
+use web_sys::AbortController;
+use yew::{
+    hook, use_effect_with_deps, use_force_update, use_mut_ref, use_state, UseForceUpdateHandle,
+    UseStateHandle,
+};
+
+#[allow(type_alias_bounds)]
+type StreamItem<A: Action> = <A::Response as IntoJsonStream>::Item;
+
+/// A handle for a server action that returns a stream of values.
+pub struct UseActionStreamHandle<A, T>
+where
+    A: Action,
+    A::Response: IntoJsonStream,
+{
+    loading: UseStateHandle<bool>,
+    error: UseStateHandle<Option<Rc<BoxError>>>,
+    items: Rc<RefCell<Vec<StreamItem<A>>>>,
+    // Number of the last request sent, older streams stop receiving values
+    last_request: Rc<RefCell<usize>>,
+    // Cancels the stream of the last request
+    abort_controller: Rc<RefCell<Option<AbortController>>>,
+    force_update: UseForceUpdateHandle,
+    _marker: PhantomData<T>,
+}
+
+impl<A, T> UseActionStreamHandle<A, T>
+where
+    A: Action,
+    A::Response: IntoJsonStream,
+    T: IntoRequestConfig,
+{
+    /// Returns `true` if the stream is still receiving values.
+    pub fn is_loading(&self) -> bool {
+        *self.loading
+    }
+
+    /// Returns `true` if the action or the stream failed.
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the values received so far.
+    pub fn items(&self) -> Ref<'_, [StreamItem<A>]> {
+        Ref::map(self.items.borrow(), |x| x.as_slice())
+    }
+
+    /// Returns the error if any.
+    pub fn error(&self) -> Option<&BoxError> {
+        self.error.as_deref()
+    }
+
+    /// Sends a request to the server, the values of the previous request are discarded.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused_variables)]
+    pub fn send(&self, obj: T) -> Result<(), BoxError> {
+        unreachable!("client only function")
+    }
+
+    /// Sends a request to the server, the values of the previous request are discarded.
+    #[cfg(target_arch = "wasm32")]
+    pub fn send(&self, obj: T) -> Result<(), BoxError> {
+        self.send_with_options(obj, RequestOptions::new())
+    }
+
+    /// Sends a request to the server using the given options.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused_variables)]
+    pub fn send_with_options(&self, obj: T, options: RequestOptions) -> Result<(), BoxError> {
+        unreachable!("client only function")
+    }
+
+    /// Sends a request to the server using the given options.
+    #[cfg(target_arch = "wasm32")]
+    pub fn send_with_options(&self, obj: T, options: RequestOptions) -> Result<(), BoxError> {
+        use super::create_action_request;
+        use crate::{error::JsError, utils::wasm::fetch_json_stream};
+
+        let request = create_action_request::<A, T>(obj, options)?;
+        let controller = AbortController::new().map_err(JsError::new)?;
+
+        // The stream of the previous request is cancelled
+        if let Some(prev) = self.abort_controller.replace(Some(controller.clone())) {
+            prev.abort();
+        }
+
+        let request_id = {
+            let mut last_request = self.last_request.borrow_mut();
+            *last_request += 1;
+            *last_request
+        };
+
+        self.items.borrow_mut().clear();
+        self.error.set(None);
+        self.loading.set(true);
+
+        let loading = self.loading.clone();
+        let error = self.error.clone();
+        let items = self.items.clone();
+        let last_request = self.last_request.clone();
+        let force_update = self.force_update.clone();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let is_current = || *last_request.borrow() == request_id;
+            let signal = controller.signal();
+            let ret = fetch_json_stream::<StreamItem<A>, _>(request, Some(&signal), |item| {
+                if !is_current() {
+                    return false;
+                }
+
+                items.borrow_mut().push(item);
+                force_update.force_update();
+                true
+            })
+            .await;
+
+            if !is_current() {
+                return;
+            }
+
+            if let Err(err) = ret {
+                error.set(Some(Rc::new(err)));
+            }
+
+            loading.set(false);
+        });
+
+        Ok(())
+    }
+}
+
+impl<A, T> Clone for UseActionStreamHandle<A, T>
+where
+    A: Action,
+    A::Response: IntoJsonStream,
+{
+    fn clone(&self) -> Self {
+        Self {
+            loading: self.loading.clone(),
+            error: self.error.clone(),
+            items: self.items.clone(),
+            last_request: self.last_request.clone(),
+            abort_controller: self.abort_controller.clone(),
+            force_update: self.force_update.clone(),
+            _marker: self._marker,
+        }
+    }
+}
+
+impl<A, T> Debug for UseActionStreamHandle<A, T>
+where
+    A: Action,
+    A::Response: IntoJsonStream,
+    StreamItem<A>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UseActionStreamHandle")
+            .field("loading", &self.loading)
+            .field("error", &self.error)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+/// Returns a handle to execute an action that returns a `JsonStream`,
+/// the values are available as soon the server sends them.
+///
+/// # Example
+/// ```rust,ignore
+/// #[page_component("/export")]
+/// fn ExportPage() -> yew::Html {
+///     let export = use_action_stream::<ExportTodos, _>();
+///
+///     html! {
+///         <>
+///         <button onclick={move |_| export.send(()).unwrap()}>{"Export"}</button>
+///         <ul>{for export.items().iter().map(|todo| html! { <li>{&todo.title}</li> })}</ul>
+///         </>
+///     }
+/// }
+/// ```
+#[hook]
+pub fn use_action_stream<A, T>() -> UseActionStreamHandle<A, T>
+where
+    A: Action,
+    A::Response: IntoJsonStream,
+    T: IntoRequestConfig,
+{
+    let loading = use_state(|| false);
+    let error = use_state(|| None);
+    let items = use_mut_ref(Vec::new);
+    let last_request = use_mut_ref(|| 0);
+    let abort_controller = use_mut_ref(|| None::<AbortController>);
+    let force_update = use_force_update();
+
+    // The stream is cancelled when the component is unmounted
+    {
+        let last_request = last_request.clone();
+        let abort_controller = abort_controller.clone();
+        use_effect_with_deps(
+            move |_| {
+                move || {
+                    *last_request.borrow_mut() += 1;
+                    if let Some(controller) = abort_controller.take() {
+                        controller.abort();
+                    }
+                }
+            },
+            (),
+        );
+    }
+
+    UseActionStreamHandle {
+        loading,
+        error,
+        items,
+        last_request,
+        abort_controller,
+        force_update,
+        _marker: PhantomData,
+    }
+}
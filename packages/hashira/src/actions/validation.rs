@@ -0,0 +1,318 @@
+use crate::web::{IntoResponse, Json, Response};
+use http::StatusCode;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Errors of the fields of an input.
+///
+/// Actions can return this error to reject the input, the response is a `422 Unprocessable Entity`
+/// with the errors as json in the form `{ "errors": { "field": ["message"] } }`.
+///
+/// # Example
+/// ```rust,ignore
+/// #[action]
+/// async fn CreateTodo(form: Form<NewTodo>) -> Result<Json<Todo>, ValidationErrors> {
+///     let mut errors = ValidationErrors::new();
+///
+///     if form.0.title.is_empty() {
+///         errors.add("title", "title cannot be empty");
+///     }
+///
+///     errors.into_result()?;
+///     todo!()
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationErrors {
+    errors: IndexMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    /// Constructs an empty errors.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an error message to the given field.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) -> &mut Self {
+        self.errors
+            .entry(field.into())
+            .or_default()
+            .push(message.into());
+        self
+    }
+
+    /// Returns this errors with an error message for the given field.
+    pub fn with(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.add(field, message);
+        self
+    }
+
+    /// Returns the error messages of the given field.
+    pub fn get(&self, field: &str) -> &[String] {
+        self.errors.get(field).map(|x| x.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns the first error message of the given field.
+    pub fn first(&self, field: &str) -> Option<&str> {
+        self.get(field).first().map(|x| x.as_str())
+    }
+
+    /// Returns `true` if the given field have errors.
+    pub fn contains(&self, field: &str) -> bool {
+        !self.get(field).is_empty()
+    }
+
+    /// Returns an iterator over the fields and its error messages.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.errors.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+
+    /// Returns the number of fields with errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Returns `true` if there is no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `Ok` if there is no errors, otherwise returns this errors.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "validation failed")?;
+
+        for (idx, (field, messages)) in self.iter().enumerate() {
+            let sep = if idx == 0 { ": " } else { "; " };
+            write!(f, "{sep}{field}: {}", messages.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        let mut res = Json(self).into_response();
+        *res.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+        res
+    }
+}
+
+impl ValidationErrors {
+    /// Attempts to read the errors from an error response.
+    pub(crate) fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+/// Validates a value.
+///
+/// This can be implemented using `#[derive(Validate)]`, the input of an action
+/// can be validated using the `Valid` extractor, for example `Valid<Form<T>>`.
+pub trait Validate {
+    /// Returns the errors of the fields of this value, if any.
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+impl<T: Validate> Validate for crate::web::Form<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.0.validate()
+    }
+}
+
+impl<T: Validate> Validate for Json<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.0.validate()
+    }
+}
+
+/// The rules used by `#[derive(Validate)]`.
+#[doc(hidden)]
+pub mod rules {
+    /// A value that can be empty.
+    pub trait Required {
+        fn is_present(&self) -> bool;
+    }
+
+    impl Required for String {
+        fn is_present(&self) -> bool {
+            !self.trim().is_empty()
+        }
+    }
+
+    impl Required for &str {
+        fn is_present(&self) -> bool {
+            !self.trim().is_empty()
+        }
+    }
+
+    impl<T> Required for Vec<T> {
+        fn is_present(&self) -> bool {
+            !self.is_empty()
+        }
+    }
+
+    impl<T> Required for Option<T> {
+        fn is_present(&self) -> bool {
+            self.is_some()
+        }
+    }
+
+    /// A value with a length, `None` if there is no value.
+    pub trait Length {
+        fn length(&self) -> Option<usize>;
+    }
+
+    impl Length for String {
+        fn length(&self) -> Option<usize> {
+            Some(self.chars().count())
+        }
+    }
+
+    impl Length for &str {
+        fn length(&self) -> Option<usize> {
+            Some(self.chars().count())
+        }
+    }
+
+    impl<T> Length for Vec<T> {
+        fn length(&self) -> Option<usize> {
+            Some(self.len())
+        }
+    }
+
+    impl<T: Length> Length for Option<T> {
+        fn length(&self) -> Option<usize> {
+            self.as_ref().and_then(|x| x.length())
+        }
+    }
+
+    /// A text value, `None` if there is no value.
+    pub trait Text {
+        fn text(&self) -> Option<&str>;
+    }
+
+    impl Text for String {
+        fn text(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl Text for &str {
+        fn text(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl<T: Text> Text for Option<T> {
+        fn text(&self) -> Option<&str> {
+            self.as_ref().and_then(|x| x.text())
+        }
+    }
+
+    pub fn required<T: Required>(value: &T) -> bool {
+        value.is_present()
+    }
+
+    pub fn min_length<T: Length>(value: &T, min: usize) -> bool {
+        value.length().map(|len| len >= min).unwrap_or(true)
+    }
+
+    pub fn max_length<T: Length>(value: &T, max: usize) -> bool {
+        value.length().map(|len| len <= max).unwrap_or(true)
+    }
+
+    pub fn min<T: PartialOrd>(value: &T, min: T) -> bool {
+        *value >= min
+    }
+
+    pub fn max<T: PartialOrd>(value: &T, max: T) -> bool {
+        *value <= max
+    }
+
+    pub fn email<T: Text>(value: &T) -> bool {
+        let Some(text) = value.text() else {
+            return true;
+        };
+
+        match text.split_once('@') {
+            Some((user, domain)) => {
+                !user.is_empty()
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && domain.contains('.')
+                    && !text.contains(char::is_whitespace)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rules, ValidationErrors};
+    use crate::web::IntoResponse;
+    use http::StatusCode;
+
+    #[test]
+    fn validation_errors_test() {
+        let errors = ValidationErrors::new()
+            .with("title", "title is required")
+            .with("title", "title is too short")
+            .with("priority", "invalid priority");
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.first("title"), Some("title is required"));
+        assert!(errors.get("description").is_empty());
+
+        let json = serde_json::to_value(&errors).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "errors": {
+                    "title": ["title is required", "title is too short"],
+                    "priority": ["invalid priority"]
+                }
+            })
+        );
+
+        let res = errors.clone().into_response();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let text = json.to_string();
+        assert_eq!(ValidationErrors::from_json(&text), Some(errors));
+        assert!(ValidationErrors::new().into_result().is_ok());
+    }
+
+    #[test]
+    fn rules_test() {
+        assert!(rules::required(&String::from("a")));
+        assert!(!rules::required(&String::from("  ")));
+        assert!(!rules::required(&None::<u32>));
+
+        assert!(rules::min_length(&String::from("abc"), 3));
+        assert!(!rules::max_length(&String::from("abc"), 2));
+        assert!(rules::min_length(&None::<String>, 3));
+
+        assert!(rules::min(&5, 1) && rules::max(&5, 5));
+        assert!(!rules::min(&0.5, 1.0));
+
+        assert!(rules::email(&String::from("homura@madoka.com")));
+        assert!(!rules::email(&String::from("homura.com")));
+        assert!(!rules::email(&String::from("homura@madoka")));
+    }
+}
@@ -1,10 +1,10 @@
 use super::{
     router::{PageRouter, PageRouterWrapper},
-    AppData, AppNested, AppService, AppServiceInner, DefaultHeaders, Handler, LayoutContext,
-    RequestContext,
+    run_layers, AppData, AppNested, AppService, AppServiceInner, BoxMiddleware, DefaultHeaders,
+    Handler, LayoutContext, Middleware, Next, RequestContext,
 };
 use crate::{
-    actions::Action,
+    actions::{Action, ActionGuard},
     components::{
         error::{ErrorPage, NotFoundPage},
         id::PageId,
@@ -26,10 +26,14 @@ type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + Sync + 'static>>;
 /// A function that renders the base `index.html`.
 pub type RenderLayout = Arc<dyn Fn(LayoutContext) -> BoxedFuture<Html> + Send + Sync>;
 
+type BoxHandler = Arc<dyn Fn(RequestContext, Body) -> BoxFuture<Response> + Send + Sync>;
+
 /// A handler for a request.
-pub struct PageHandler(
-    pub(crate) Box<dyn Fn(RequestContext, Body) -> BoxFuture<Response> + Send + Sync>,
-);
+pub struct PageHandler {
+    handler: BoxHandler,
+    // The middlewares that wrap the handler, from outermost to innermost
+    layers: Vec<BoxMiddleware>,
+}
 
 impl PageHandler {
     pub fn new<H, Args>(handler: H) -> Self
@@ -40,7 +44,7 @@ impl PageHandler {
         H::Output: IntoResponse,
         <Args as FromRequest>::Fut: Send,
     {
-        PageHandler(Box::new(move |ctx, mut body| {
+        let handler: BoxHandler = Arc::new(move |ctx, mut body| {
             let handler = handler.clone();
             Box::pin(async move {
                 let args = match Args::from_request(&ctx, &mut body).await {
@@ -52,11 +56,45 @@ impl PageHandler {
                 let ret = handler.call(args).await;
                 ret.into_response()
             })
-        }))
+        });
+
+        PageHandler {
+            handler,
+            layers: vec![],
+        }
     }
 
     pub fn call(&self, ctx: RequestContext, body: Body) -> BoxFuture<Response> {
-        (self.0)(ctx, body)
+        self.call_with(ctx, body, |ctx, body, handler| {
+            Box::pin(handler.run(ctx, body))
+        })
+    }
+
+    /// Calls the handler without running the middlewares.
+    pub(crate) fn call_handler(&self, ctx: RequestContext, body: Body) -> BoxFuture<Response> {
+        (self.handler)(ctx, body)
+    }
+
+    /// Runs the middlewares around the given function, which receives the handler to call.
+    pub(crate) fn call_with<F>(&self, ctx: RequestContext, body: Body, f: F) -> BoxFuture<Response>
+    where
+        F: FnOnce(RequestContext, Body, Next) -> BoxFuture<Response> + Send + 'static,
+    {
+        let handler = self.handler.clone();
+        let handler = Next::new(move |ctx, body| handler(ctx, body));
+        run_layers(&self.layers, ctx, body, move |ctx, body| {
+            f(ctx, body, handler)
+        })
+    }
+
+    /// Adds a middleware that runs after the current ones.
+    pub(crate) fn push_layer(&mut self, middleware: BoxMiddleware) {
+        self.layers.push(middleware);
+    }
+
+    /// Adds the middlewares of an outer scope, which run before the current ones.
+    pub(crate) fn wrap_layers(&mut self, layers: &[BoxMiddleware]) {
+        self.layers.splice(0..0, layers.iter().cloned());
     }
 }
 
@@ -92,6 +130,7 @@ pub struct App<BASE> {
     server_error_router: ServerErrorRouter,
     app_data: AppData,
     default_headers: HeaderMap,
+    layers: Vec<BoxMiddleware>,
     _marker: PhantomData<BASE>,
 
     #[cfg(feature = "hooks")]
@@ -109,6 +148,7 @@ impl<BASE> App<BASE> {
             server_error_router: ServerErrorRouter::new(),
             app_data: Default::default(),
             default_headers: Default::default(),
+            layers: Vec::new(),
             _marker: PhantomData,
 
             #[cfg(feature = "hooks")]
@@ -178,7 +218,9 @@ where
         {
             use super::InsertInRootRoute;
 
-            for (sub, route) in scope.server_router {
+            for (sub, mut route) in scope.server_router {
+                route.handler_mut().wrap_layers(&scope.layers);
+
                 let path = match sub.as_str() {
                     "/" => base_path.to_owned(),
                     _ if route.extensions().get::<InsertInRootRoute>().is_some() => sub.to_owned(),
@@ -199,8 +241,13 @@ where
                 format!("{base_path}{sub}")
             };
 
-            self.page_router
-                .insert(&path, route.with_path(path.clone()));
+            crate::routing::register_base_path(route.id().clone(), base_path);
+
+            let route = route
+                .with_path(path.clone())
+                .with_layouts(&scope.layouts);
+
+            self.page_router.insert(&path, route);
         }
 
         self
@@ -236,6 +283,16 @@ where
             });
 
             route.extensions_mut().insert(HandlerKind::Page);
+            route
+                .extensions_mut()
+                .insert(crate::server::StaticParams::of::<COMP>());
+
+            if let Some(revalidate) = COMP::revalidate() {
+                route
+                    .extensions_mut()
+                    .insert(crate::server::Revalidate(revalidate));
+            }
+
             self.route(route)
         }
 
@@ -318,23 +375,43 @@ where
     {
         #[cfg(not(feature = "client"))]
         {
+            use crate::actions::handle_action;
+            use crate::routing::HandlerKind;
+
+            let path = A::route().to_string();
+            let method = A::method();
+            let mut route = Route::new(&path, method, |ctx, body| {
+                handle_action::<A>(ctx, body, None)
+            });
+
+            route.extensions_mut().insert(HandlerKind::Action);
+            self.route(route)
+        }
+
+        #[cfg(feature = "client")]
+        self
+    }
+
+    /// Register a server action that can only be called if the given guard succeed.
+    ///
+    /// The guard runs after the guards declared in the action, if any.
+    #[cfg_attr(feature = "client", allow(unused_variables))]
+    pub fn action_with_guard<A, G>(self, guard: G) -> Self
+    where
+        A: Action,
+        G: ActionGuard,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            use crate::actions::handle_action;
             use crate::routing::HandlerKind;
-            use crate::web::IntoJsonResponse;
 
+            let guard = Arc::new(guard) as Arc<dyn ActionGuard>;
             let path = A::route().to_string();
             let method = A::method();
-            let mut route = Route::new(
-                &path,
-                method,
-                |ctx: RequestContext, body: Body| async move {
-                    let output = crate::try_response!(A::call(ctx, body).await);
-                    let json_res = crate::try_response!(output.into_json_response());
-                    let (parts, body) = json_res.into_parts();
-                    let bytes = crate::try_response!(serde_json::to_vec(&body));
-                    let body = Body::from(bytes);
-                    Response::from_parts(parts, body)
-                },
-            );
+            let mut route = Route::new(&path, method, move |ctx, body| {
+                handle_action::<A>(ctx, body, Some(guard.clone()))
+            });
 
             route.extensions_mut().insert(HandlerKind::Action);
             self.route(route)
@@ -389,6 +466,33 @@ where
         self
     }
 
+    /// Caches the rendered pages in the server.
+    ///
+    /// Only the pages that set a public `CachePolicy` with a `max-age` are stored,
+    /// the `PageCache` is also added to the app data so can be injected in server actions
+    /// to invalidate the cached pages.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn page_cache(mut self, page_cache: crate::server::PageCache) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.app_data.insert(page_cache);
+        }
+
+        self
+    }
+
+    /// Stores the rendered pages that declare a `revalidate` interval and serve them from the store,
+    /// the stale pages are regenerated in the background.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn static_regeneration(mut self, regeneration: crate::server::StaticRegeneration) -> Self {
+        #[cfg(not(feature = "client"))]
+        {
+            self.app_data.insert(regeneration);
+        }
+
+        self
+    }
+
     /// Adds headers to always append in a response.
     #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
     pub fn default_headers(mut self, headers: DefaultHeaders) -> Self {
@@ -399,6 +503,23 @@ where
         self
     }
 
+    /// Adds a middleware that wraps all the routes of the app, including the nested routes.
+    ///
+    /// The middlewares run in the order they were added, before the middlewares of
+    /// the nested scopes and routes.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn layer<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            self.layers.push(Arc::new(middleware));
+        }
+
+        self
+    }
+
     /// Adds the given `Hooks`.
     #[cfg(feature = "hooks")]
     pub fn hooks(mut self, hooks: crate::events::Hooks) -> Self {
@@ -414,12 +535,13 @@ where
     {
         let App {
             layout,
-            server_router,
+            mut server_router,
             page_router: client_router,
             client_error_router,
             server_error_router,
             default_headers,
             mut app_data,
+            layers,
             _marker: _,
 
             #[cfg(feature = "hooks")]
@@ -434,6 +556,12 @@ where
             Arc::new(render_layout)
         });
 
+        if !layers.is_empty() {
+            for route in server_router.routes_mut() {
+                route.handler_mut().wrap_layers(&layers);
+            }
+        }
+
         #[cfg(feature = "hooks")]
         let hooks = Arc::new(hooks);
 
@@ -454,6 +582,7 @@ where
             client_error_router,
             server_error_router,
             default_headers,
+            layers,
 
             #[cfg(feature = "hooks")]
             hooks,
@@ -509,6 +638,7 @@ where
                         <COMP ..props/>
                     }
                 }),
+                layouts: COMP::layout().into_iter().collect(),
             },
         );
     }
@@ -1,6 +1,7 @@
-use crate::actions::Action;
+use super::Middleware;
+use crate::actions::{Action, ActionGuard};
 use crate::components::id::PageId;
-use crate::components::PageComponent;
+use crate::components::{PageComponent, PageLayout};
 use crate::routing::{ClientPageRoute, Route};
 use serde::de::DeserializeOwned;
 use std::{collections::HashMap, marker::PhantomData};
@@ -24,6 +25,13 @@ pub struct AppNested<BASE> {
     // Inner page router
     pub(crate) page_router: HashMap<String, ClientPageRoute>,
 
+    // Layouts that wrap all the pages of this route, from outermost to innermost
+    pub(crate) layouts: Vec<PageLayout>,
+
+    // Middlewares that wrap all the server routes of this route, from outermost to innermost
+    #[cfg(not(feature = "client"))]
+    pub(crate) layers: Vec<super::BoxMiddleware>,
+
     //
     _marker: PhantomData<BASE>,
 }
@@ -38,6 +46,9 @@ where
             #[cfg(not(feature = "client"))]
             server_router: HashMap::new(),
             page_router: HashMap::new(),
+            layouts: Vec::new(),
+            #[cfg(not(feature = "client"))]
+            layers: Vec::new(),
             _marker: PhantomData,
         }
     }
@@ -54,6 +65,35 @@ where
         self
     }
 
+    /// Adds a layout that wraps all the pages of this nested route.
+    ///
+    /// Layouts are applied from outermost to innermost in the order they are added,
+    /// and are placed outside the layout declared by each page.
+    pub fn layout<LAYOUT>(mut self) -> Self
+    where
+        LAYOUT: BaseComponent<Properties = ChildrenProps>,
+    {
+        self.layouts.push(PageLayout::of::<LAYOUT>());
+        self
+    }
+
+    /// Adds a middleware that wraps all the routes of this nested route.
+    ///
+    /// The middlewares run in the order they were added, after the middlewares of the app
+    /// and before the middlewares of each route.
+    #[cfg_attr(feature = "client", allow(unused_mut, unused_variables))]
+    pub fn layer<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            self.layers.push(std::sync::Arc::new(middleware));
+        }
+
+        self
+    }
+
     /// Adds a page for the given route.
     #[cfg_attr(feature = "client", allow(unused_variables))]
     pub fn page<COMP>(mut self) -> Self
@@ -86,6 +126,16 @@ where
             });
 
             route.extensions_mut().insert(HandlerKind::Page);
+            route
+                .extensions_mut()
+                .insert(crate::server::StaticParams::of::<COMP>());
+
+            if let Some(revalidate) = COMP::revalidate() {
+                route
+                    .extensions_mut()
+                    .insert(crate::server::Revalidate(revalidate));
+            }
+
             self.route(route)
         }
 
@@ -101,19 +151,43 @@ where
     {
         #[cfg(not(feature = "client"))]
         {
-            use crate::app::RequestContext;
-            use crate::web::{Body, IntoJsonResponse, Response};
+            use crate::actions::handle_action;
             use crate::routing::HandlerKind;
-            
+
+            let route = A::route().to_string();
+            let method = A::method();
+            let mut route = Route::new(&route, method, |ctx, body| {
+                handle_action::<A>(ctx, body, None)
+            });
+
+            route.extensions_mut().insert(InsertInRootRoute);
+            route.extensions_mut().insert(HandlerKind::Action);
+            self.route(route)
+        }
+
+        #[cfg(feature = "client")]
+        self
+    }
+
+    /// Register a server action that can only be called if the given guard succeed.
+    ///
+    /// The guard runs after the guards declared in the action, if any.
+    #[cfg_attr(feature = "client", allow(unused_variables))]
+    pub fn action_with_guard<A, G>(self, guard: G) -> Self
+    where
+        A: Action,
+        G: ActionGuard,
+    {
+        #[cfg(not(feature = "client"))]
+        {
+            use crate::actions::handle_action;
+            use crate::routing::HandlerKind;
+
+            let guard = std::sync::Arc::new(guard) as std::sync::Arc<dyn ActionGuard>;
             let route = A::route().to_string();
             let method = A::method();
-            let mut route = Route::new(&route, method, |ctx: RequestContext, body: Body| async move {
-                let output = crate::try_response!(A::call(ctx, body).await);
-                let json_res = crate::try_response!(output.into_json_response());
-                let (parts, body) = json_res.into_parts();
-                let bytes = crate::try_response!(serde_json::to_vec(&body));
-                let body = Body::from(bytes);
-                Response::from_parts(parts, body)
+            let mut route = Route::new(&route, method, move |ctx, body| {
+                handle_action::<A>(ctx, body, Some(guard.clone()))
             });
 
             route.extensions_mut().insert(InsertInRootRoute);
@@ -154,6 +228,7 @@ where
                         <COMP ..props/>
                     }
                 }),
+                layouts: COMP::layout().into_iter().collect(),
             },
         );
     }
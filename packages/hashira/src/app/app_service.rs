@@ -1,13 +1,20 @@
-use super::{router::PageRouterWrapper, AppData, RequestContext};
+use super::{router::PageRouterWrapper, run_layers, AppData, BoxMiddleware, Next, RequestContext};
 use crate::{
+    actions::{remove_flash_cookie, EmbeddedCsrfToken},
+    components::{
+        HASHIRA_ACTION_FLASH_COOKIE, HASHIRA_INVALIDATE_TAGS_HEADER, HASHIRA_PAGE_DATA_HEADER,
+    },
     error::ServerError,
+    error::BoxError,
     routing::{
-        ErrorRouter, HandlerKind, Params, ServerErrorRouter, ServerRouter, ServerRouterMatchError,
+        ErrorRouter, HandlerKind, Params, Route, RouteMethod, ServerErrorRouter, ServerRouter,
+        ServerRouterMatchError,
     },
-    web::{Body, IntoResponse, Request, Response},
+    server::{PageArtifact, PageCache, StaticRegeneration},
+    web::{Body, IntoResponse, Request, RequestExt, Response},
 };
-use http::{HeaderMap, StatusCode};
-use std::sync::Arc;
+use http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use std::{sync::Arc, time::Duration};
 
 pub(crate) struct AppServiceInner {
     pub(crate) server_router: ServerRouter,
@@ -16,6 +23,7 @@ pub(crate) struct AppServiceInner {
     pub(crate) client_error_router: Arc<ErrorRouter>,
     pub(crate) default_headers: HeaderMap,
     pub(crate) app_data: Arc<AppData>,
+    pub(crate) layers: Vec<BoxMiddleware>,
 
     #[cfg(feature = "hooks")]
     pub(crate) hooks: Arc<crate::events::Hooks>,
@@ -61,6 +69,12 @@ impl AppService {
         &self.0.client_router
     }
 
+    /// Returns the server router.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn server_router(&self) -> &ServerRouter {
+        &self.0.server_router
+    }
+
     /// Returns the router for handling error pages on the client.
     #[cfg_attr(not(feature = "client"), allow(dead_code))]
     pub(crate) fn error_router(&self) -> &Arc<ErrorRouter> {
@@ -121,6 +135,69 @@ impl AppService {
     }
 
     async fn handle_request(&self, req: Request<()>, body: Body) -> Response {
+        #[cfg(not(feature = "client"))]
+        {
+            use crate::{
+                actions::{CsrfToken, EmbeddedCsrfToken},
+                web::ResponseExt,
+            };
+
+            // Each client receives a token to protect the actions against CSRF
+            let mut req = req;
+            if req.extensions().get::<EmbeddedCsrfToken>().is_none() {
+                req.extensions_mut().insert(EmbeddedCsrfToken::default());
+            }
+
+            let new_token = match CsrfToken::from_request(&req) {
+                Some(token) => {
+                    req.extensions_mut().insert(token);
+                    None
+                }
+                None => {
+                    let token = CsrfToken::generate();
+                    req.extensions_mut().insert(token.clone());
+                    Some(token)
+                }
+            };
+
+            let is_page_data = req.headers().contains_key(HASHIRA_PAGE_DATA_HEADER);
+            let mut res = self.route_request(req, body).await;
+
+            // The token is only stored when the client receives a page
+            if let Some(token) = new_token {
+                let is_html = res
+                    .content_type()
+                    .map(|x| x.essence_str() == mime::TEXT_HTML.essence_str())
+                    .unwrap_or_default();
+
+                if is_html || is_page_data {
+                    res.headers_mut()
+                        .append(header::SET_COOKIE, token.to_cookie());
+                }
+            }
+
+            res
+        }
+
+        #[cfg(feature = "client")]
+        {
+            self.route_request(req, body).await
+        }
+    }
+
+    async fn route_request(&self, req: Request<()>, body: Body) -> Response {
+        // `HEAD` requests receive the same headers than a `GET` but without the body
+        let is_head = req.method() == Method::HEAD;
+        let mut res = self.dispatch_request(req, body).await;
+
+        if is_head {
+            *res.body_mut() = Body::empty();
+        }
+
+        res
+    }
+
+    async fn dispatch_request(&self, req: Request<()>, body: Body) -> Response {
         // We remove the trailing slash from the path,
         // when adding a path we ensure it cannot end with a slash
         // and should start with a slash
@@ -133,25 +210,90 @@ impl AppService {
             path = path.trim_end_matches('/');
         }
 
-        let method = req.method().into();
+        let method: RouteMethod = req.method().into();
         let req = Arc::new(req);
 
-        match self.0.server_router.at(path, method) {
+        let mtch = match self.0.server_router.at(path, method) {
+            // Any `GET` route also handles `HEAD`
+            Err(ServerRouterMatchError::MethodMismatch(allowed))
+                if method == RouteMethod::HEAD && allowed.matches(&RouteMethod::GET) =>
+            {
+                self.0.server_router.at(path, RouteMethod::GET)
+            }
+            mtch => mtch,
+        };
+
+        match mtch {
             Ok(mtch) => {
                 let route = mtch.value;
-                let params = mtch.params;
-                let ctx = self.create_context(req.clone(), params, None);
-
-                let res = route.handler().call(ctx, body).await;
-                let status = res.status();
+                let handler_kind = route.extensions().get::<HandlerKind>();
 
                 // Only component pages render error by default
-                let should_render = route
-                    .extensions()
-                    .get::<HandlerKind>()
+                let should_render = handler_kind
                     .map(|kind| kind == &HandlerKind::Page)
                     .unwrap_or_default();
 
+                // Pages showing the result of a native form submission are never stored
+                let has_action_flash =
+                    should_render && req.cookie(HASHIRA_ACTION_FLASH_COOKIE).is_some();
+
+                // Pages with a revalidate interval are served from the store, if any
+                let static_regeneration = self
+                    .static_regeneration_for(&req, route)
+                    .filter(|_| !has_action_flash)
+                    .map(|(regeneration, revalidate)| (regeneration.clone(), revalidate));
+
+                let page_cache = self
+                    .page_cache_for(
+                        &req,
+                        should_render && static_regeneration.is_none() && !has_action_flash,
+                    )
+                    .cloned();
+
+                // The middlewares of the route also run for the stored and cached pages
+                let this = self.clone();
+                let path = path.to_owned();
+                let ctx = self.create_context(req.clone(), mtch.params, None);
+                let mut res = route
+                    .handler()
+                    .call_with(ctx, body, move |ctx, body, handler| {
+                        Box::pin(async move {
+                            this.serve_route(
+                                ctx,
+                                body,
+                                handler,
+                                &path,
+                                static_regeneration,
+                                page_cache,
+                            )
+                            .await
+                        })
+                    })
+                    .await;
+
+                let status = res.status();
+
+                // Actions can invalidate the cached pages, native form submissions are redirected
+                if handler_kind == Some(&HandlerKind::Action)
+                    && (status.is_success() || status == StatusCode::SEE_OTHER)
+                {
+                    self.invalidate_cached_pages(&res);
+                }
+
+                // Pages respond with the html or the page data depending on the request
+                if should_render {
+                    res.headers_mut().append(
+                        header::VARY,
+                        HeaderValue::from_static(HASHIRA_PAGE_DATA_HEADER),
+                    );
+                }
+
+                // The action result was already rendered
+                if has_action_flash {
+                    res.headers_mut()
+                        .append(header::SET_COOKIE, remove_flash_cookie());
+                }
+
                 if status.is_client_error() || status.is_server_error() {
                     // SAFETY: We already check the status is an error
                     let error = ServerError::from_response(res);
@@ -160,16 +302,198 @@ impl AppService {
 
                 res
             }
-            Err(ServerRouterMatchError::MethodMismatch) => {
-                let error = ServerError::from_status(StatusCode::METHOD_NOT_ALLOWED);
-                self.handle_error(req, error, true).await
-            }
-            Err(_) => {
+            Err(err) => {
+                let allow = match err {
+                    ServerRouterMatchError::MethodMismatch(allowed) => Some(allow_header(allowed)),
+                    _ => None,
+                };
+
+                // Routes without an `OPTIONS` handler respond with the allowed methods,
                 // we treat any other error as 404
-                let error = ServerError::from_status(StatusCode::NOT_FOUND);
-                self.handle_error(req, error, true).await
+                let status = match &allow {
+                    Some(_) if method == RouteMethod::OPTIONS => StatusCode::NO_CONTENT,
+                    Some(_) => StatusCode::METHOD_NOT_ALLOWED,
+                    None => StatusCode::NOT_FOUND,
+                };
+
+                // The middlewares of the app also run for the automatic responses
+                let ctx = self.create_context(req.clone(), Params::default(), None);
+                let res = run_layers(&self.0.layers, ctx, body, move |_, _| {
+                    Box::pin(async move { status.into_response() })
+                })
+                .await;
+
+                let mut res = if res.status().is_client_error() || res.status().is_server_error() {
+                    let error = ServerError::from_response(res);
+                    self.handle_error(req, error, true).await
+                } else {
+                    res
+                };
+
+                if let Some(allow) = allow {
+                    res.headers_mut().insert(header::ALLOW, allow);
+                }
+
+                res
+            }
+        }
+    }
+
+    // Returns the response of the route handler, or the page from the store or the cache, if any
+    async fn serve_route(
+        &self,
+        ctx: RequestContext,
+        body: Body,
+        handler: Next,
+        path: &str,
+        static_regeneration: Option<(StaticRegeneration, Duration)>,
+        page_cache: Option<PageCache>,
+    ) -> Response {
+        let req = ctx.inner.request.clone();
+        let artifact = PageArtifact::from_request(&req);
+
+        if let Some((regeneration, revalidate)) = &static_regeneration {
+            if let Some(page) = regeneration.get(path, artifact).await {
+                if page.is_stale(*revalidate) {
+                    self.regenerate_in_background(regeneration, &req, path, artifact);
+                }
+
+                return page.to_response(artifact);
+            }
+        }
+
+        // Returns the page from the cache, if any
+        let cache_key = page_cache
+            .as_ref()
+            .map(|cache| cache.key(&req, ctx.params()));
+
+        if let (Some(cache), Some(key)) = (&page_cache, &cache_key) {
+            if let Some(res) = cache.get(key) {
+                return res;
             }
         }
+
+        // Stored pages are shared by all the clients, so are rendered without the client data
+        let ctx = match static_regeneration {
+            Some(_) => {
+                let page_req = Arc::new(artifact.page_request(&req));
+                self.create_context(page_req, ctx.params().clone(), None)
+            }
+            None => ctx,
+        };
+
+        let mut res = handler.run(ctx, body).await;
+
+        // Pages rendering the token of the client are not shared with other clients
+        if let Some(embedded) = req.extensions().get::<EmbeddedCsrfToken>() {
+            res.extensions_mut().insert(embedded.clone());
+        }
+
+        if let Some((regeneration, _)) = &static_regeneration {
+            return regeneration.store(path, artifact, res).await;
+        }
+
+        match (page_cache, cache_key) {
+            (Some(cache), Some(key)) => cache.store(key, res).await,
+            _ => res,
+        }
+    }
+
+    // Only `GET` requests to pages are cached
+    fn page_cache_for(&self, req: &Request<()>, is_page: bool) -> Option<&PageCache> {
+        if !is_page || req.method() != Method::GET {
+            return None;
+        }
+
+        self.0.app_data.get::<PageCache>()
+    }
+
+    // Only `GET` requests to pages with a revalidate interval are stored
+    fn static_regeneration_for(
+        &self,
+        req: &Request<()>,
+        route: &Route,
+    ) -> Option<(&StaticRegeneration, Duration)> {
+        #[cfg(not(feature = "client"))]
+        {
+            use crate::server::Revalidate;
+
+            if req.method() != Method::GET {
+                return None;
+            }
+
+            let Revalidate(revalidate) = route.extensions().get::<Revalidate>()?;
+            let regeneration = self.0.app_data.get::<StaticRegeneration>()?;
+            Some((regeneration, *revalidate))
+        }
+
+        #[cfg(feature = "client")]
+        {
+            let _ = (req, route);
+            None
+        }
+    }
+
+    fn regenerate_in_background(
+        &self,
+        regeneration: &StaticRegeneration,
+        req: &Request<()>,
+        path: &str,
+        artifact: PageArtifact,
+    ) {
+        // The page is already being regenerated
+        let Some(guard) = regeneration.start_regeneration(path, artifact) else {
+            return;
+        };
+
+        let this = self.clone();
+        let regeneration = regeneration.clone();
+        let req = Arc::new(artifact.page_request(req));
+        let path = path.to_owned();
+
+        crate::server::spawn_background(async move {
+            // The regeneration ends when dropped, even if the render panics
+            let _guard = guard;
+
+            if let Err(err) = this.regenerate(&regeneration, req, &path, artifact).await {
+                log::error!("failed to regenerate page `{path}`: {err}");
+            }
+        });
+    }
+
+    async fn regenerate(
+        &self,
+        regeneration: &StaticRegeneration,
+        req: Arc<Request<()>>,
+        path: &str,
+        artifact: PageArtifact,
+    ) -> Result<(), BoxError> {
+        let mtch = self.0.server_router.at(path, RouteMethod::GET)?;
+        let ctx = self.create_context(req, mtch.params, None);
+        // The page request has no client data, so the middlewares of the route are skipped
+        let res = mtch.value.handler().call_handler(ctx, Body::empty()).await;
+
+        if !res.status().is_success() {
+            return Err(format!("page responded with status {}", res.status()).into());
+        }
+
+        regeneration.store(path, artifact, res).await;
+        Ok(())
+    }
+
+    fn invalidate_cached_pages(&self, res: &Response) {
+        let Some(page_cache) = self.0.app_data.get::<PageCache>() else {
+            return;
+        };
+
+        if let Some(tags) = res
+            .headers()
+            .get(HASHIRA_INVALIDATE_TAGS_HEADER)
+            .and_then(|x| x.to_str().ok())
+        {
+            let tags = tags.split(',').map(str::trim).collect::<Vec<_>>();
+            page_cache.invalidate_tags(&tags);
+        }
     }
 
     async fn handle_error(
@@ -230,6 +554,23 @@ impl Clone for AppService {
     }
 }
 
+// Returns the value of the `Allow` header, which includes the methods handled automatically
+fn allow_header(allowed: RouteMethod) -> HeaderValue {
+    let mut allowed = allowed | RouteMethod::OPTIONS;
+    if allowed.matches(&RouteMethod::GET) {
+        allowed = allowed | RouteMethod::HEAD;
+    }
+
+    let methods = allowed
+        .methods()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // SAFETY: The method names are valid header values
+    HeaderValue::from_str(&methods).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(dead_code, unused_imports)]
@@ -293,6 +634,116 @@ mod tests {
         assert_eq!(res5.status(), StatusCode::METHOD_NOT_ALLOWED);
     }
 
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn head_and_options_test() {
+        use http::header;
+
+        let service = App::<Base>::new()
+            .route(Route::get("/a", || async { "hello" }))
+            .route(Route::post("/a", noop))
+            .route(Route::delete("/b", noop))
+            .build();
+
+        let res1 = service
+            .handle_request(create_req("/a", Method::HEAD), Default::default())
+            .await;
+        assert_eq!(res1.status(), StatusCode::OK);
+        assert_eq!(
+            res1.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert!(res1.into_body().into_bytes().await.unwrap().is_empty());
+
+        let res2 = service
+            .handle_request(create_req("/a", Method::OPTIONS), Default::default())
+            .await;
+        assert_eq!(res2.status(), StatusCode::NO_CONTENT);
+        assert_eq!(res2.headers()[header::ALLOW], "GET, POST, HEAD, OPTIONS");
+
+        let res3 = service
+            .handle_request(create_req("/b", Method::HEAD), Default::default())
+            .await;
+        assert_eq!(res3.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res3.headers()[header::ALLOW], "DELETE, OPTIONS");
+
+        let res4 = service
+            .handle_request(create_req("/b", Method::PUT), Default::default())
+            .await;
+        assert_eq!(res4.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res4.headers()[header::ALLOW], "DELETE, OPTIONS");
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn middleware_layer_test() {
+        use crate::app::{nested, Next, RequestContext};
+        use crate::web::IntoResponse;
+        use http::HeaderValue;
+
+        // Appends the name of each middleware to the `x-layers` header
+        fn trace(name: &'static str) -> impl crate::app::Middleware {
+            move |ctx: RequestContext, body: Body, next: Next| async move {
+                let mut res = next.run(ctx, body).await;
+                let layers = match res.headers().get("x-layers") {
+                    Some(value) => format!("{name},{}", value.to_str().unwrap()),
+                    None => name.to_owned(),
+                };
+
+                res.headers_mut()
+                    .insert("x-layers", HeaderValue::from_str(&layers).unwrap());
+                res
+            }
+        }
+
+        async fn require_token(ctx: RequestContext, body: Body, next: Next) -> Response {
+            if !ctx.request().headers().contains_key("x-token") {
+                return StatusCode::UNAUTHORIZED.into_response();
+            }
+
+            next.run(ctx, body).await
+        }
+
+        let service = App::<Base>::new()
+            .layer(trace("app"))
+            .route(Route::get("/public", noop))
+            .route(Route::get("/route", noop).layer(trace("route")))
+            .nest(
+                "/admin",
+                nested()
+                    .layer(require_token)
+                    .layer(trace("admin"))
+                    .route(Route::get("/users", noop).layer(trace("users"))),
+            )
+            .build();
+
+        let res1 = service
+            .handle_request(create_req("/public", Method::GET), Default::default())
+            .await;
+        assert_eq!(res1.status(), StatusCode::OK);
+        assert_eq!(res1.headers()["x-layers"], "app");
+
+        let res2 = service
+            .handle_request(create_req("/route", Method::GET), Default::default())
+            .await;
+        assert_eq!(res2.headers()["x-layers"], "app,route");
+
+        let res3 = service
+            .handle_request(create_req("/admin/users", Method::GET), Default::default())
+            .await;
+        assert_eq!(res3.status(), StatusCode::UNAUTHORIZED);
+
+        let req = Request::builder()
+            .uri("/admin/users")
+            .header("x-token", "secret")
+            .body(())
+            .unwrap();
+
+        let res4 = service.handle_request(req, Default::default()).await;
+        assert_eq!(res4.status(), StatusCode::OK);
+        assert_eq!(res4.headers()["x-layers"], "app,admin,users");
+    }
+
     #[tokio::test]
     #[cfg(not(feature = "client"))]
     async fn nested_route_test() {
@@ -467,9 +918,11 @@ mod tests {
         );
     }
 
-    #[test]
-    #[should_panic]
-    fn invalid_page_route_test_1() {
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn page_data_request_test() {
+        use crate::components::{PageData, HASHIRA_PAGE_DATA_HEADER};
+
         #[function_component]
         fn CompA() -> yew::Html {
             yew::html! {
@@ -477,48 +930,1277 @@ mod tests {
             }
         }
 
-        crate::impl_page_component!(CompA, "");
-        let _ = App::<Base>::new().page::<CompA>().build();
+        crate::impl_page_component!(CompA, "/a");
+
+        let service = App::<Base>::new().page::<CompA>().build();
+
+        let req = Request::builder()
+            .uri("/a")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let page_data = serde_json::from_slice::<PageData>(&bytes).unwrap();
+        assert_eq!(page_data.id, crate::components::id::PageId::of::<CompA>());
+        assert_eq!(page_data.uri.path(), "/a");
+        assert!(page_data.error.is_none());
     }
 
-    #[test]
-    #[should_panic]
-    fn invalid_page_route_test_2() {
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn nested_layout_test() {
+        use crate::app::nested;
+        use crate::components::{PageComponent, PageLayout};
+
         #[function_component]
-        fn CompA() -> yew::Html {
+        fn OuterLayout(props: &ChildrenProps) -> yew::Html {
             yew::html! {
-                "test - component (a)"
+                <div id="outer">{for props.children.iter()}</div>
             }
         }
 
-        crate::impl_page_component!(CompA, "/a/");
-        let _ = App::<Base>::new().page::<CompA>().build();
-    }
+        #[function_component]
+        fn InnerLayout(props: &ChildrenProps) -> yew::Html {
+            yew::html! {
+                <div id="inner">{for props.children.iter()}</div>
+            }
+        }
 
-    #[test]
-    #[should_panic]
-    fn duplicated_error_handler_test() {
         #[function_component]
-        fn ErrorPage1() -> yew::Html {
+        fn PageLayoutComp(props: &ChildrenProps) -> yew::Html {
             yew::html! {
-                "test - not found"
+                <div id="page">{for props.children.iter()}</div>
             }
         }
 
         #[function_component]
-        fn ErrorPage2() -> yew::Html {
+        fn CompA() -> yew::Html {
             yew::html! {
-                "test - not found"
+                "test - component (a)"
             }
         }
 
-        crate::impl_page_component!(ErrorPage1);
-        crate::impl_page_component!(ErrorPage2);
+        impl PageComponent for CompA {
+            fn route() -> Option<&'static str> {
+                Some("/a")
+            }
 
-        let _ = App::<Base>::new()
-            .error_page::<ErrorPage1>(StatusCode::NOT_FOUND)
-            .error_page::<ErrorPage2>(StatusCode::NOT_FOUND)
+            fn layout() -> Option<PageLayout> {
+                Some(PageLayout::of::<PageLayoutComp>())
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .nest(
+                "/dashboard",
+                nested()
+                    .layout::<OuterLayout>()
+                    .layout::<InnerLayout>()
+                    .page::<CompA>(),
+            )
+            .build();
+
+        let res = send_request_get_text(&service, "/dashboard/a", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = res.body();
+        let outer = body.find("id=\"outer\"").expect("outer layout was not rendered");
+        let inner = body.find("id=\"inner\"").expect("inner layout was not rendered");
+        let page = body.find("id=\"page\"").expect("page layout was not rendered");
+        let content = body.find("test - component (a)").unwrap();
+
+        assert!(outer < inner && inner < page && page < content, "body: {body}");
+
+        let url = crate::routing::PageUrl::for_page::<CompA>()
+            .push_segment("a")
+            .build();
+        assert_eq!(url.path(), "/dashboard/a");
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn native_form_action_test() {
+        use crate::actions::{Action, ActionFlash};
+        use crate::app::RequestContext;
+        use crate::components::{
+            PageData, HASHIRA_ACTION_FLASH_COOKIE, HASHIRA_ACTION_HEADER, HASHIRA_CSRF_COOKIE,
+            HASHIRA_CSRF_HEADER, HASHIRA_PAGE_DATA_HEADER,
+        };
+        use http::header;
+
+        struct AddTodo;
+
+        impl Action for AddTodo {
+            type Response = String;
+
+            fn route() -> &'static str {
+                "/_hashira/action/add_todo"
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(String::from("todo added")) })
+            }
+        }
+
+        #[function_component]
+        fn TodosPage() -> yew::Html {
+            yew::html! { "todos" }
+        }
+
+        crate::impl_page_component!(TodosPage, "/todos");
+
+        let service = App::<Base>::new()
+            .page::<TodosPage>()
+            .action::<AddTodo>()
             .build();
+
+        // A form submitted by the browser is redirected back with the result
+        let req = Request::post(AddTodo::route())
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::REFERER, "http://localhost:5000/todos")
+            .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}=token"))
+            .body(())
+            .unwrap();
+
+        let body = Body::from("title=hello&_csrf=token");
+        let res = service.handle_request(req, body).await;
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/todos");
+
+        let set_cookie = res.headers().get(header::SET_COOKIE).unwrap();
+        let cookie = cookie::Cookie::parse_encoded(set_cookie.to_str().unwrap()).unwrap();
+        assert_eq!(cookie.name(), HASHIRA_ACTION_FLASH_COOKIE);
+
+        // The page receives the result and removes the cookie
+        let req = Request::builder()
+            .uri("/todos")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .header(header::COOKIE, set_cookie.to_str().unwrap())
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::SET_COOKIE).is_some());
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let page_data = serde_json::from_slice::<PageData>(&bytes).unwrap();
+        let flash = page_data.action_flash.unwrap();
+        assert_eq!(
+            flash,
+            ActionFlash {
+                action: AddTodo::route().to_owned(),
+                data: Some(serde_json::json!("todo added")),
+                error: None,
+                truncated: false,
+            }
+        );
+        assert_eq!(flash.result_for::<AddTodo>().unwrap().unwrap(), "todo added");
+
+        // Actions called by the client respond with json
+        let req = Request::post(AddTodo::route())
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(HASHIRA_ACTION_HEADER, "true")
+            .header(HASHIRA_CSRF_HEADER, "token")
+            .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}=token"))
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::from("title=hello")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn validation_action_test() {
+        use crate::actions::{Action, ActionFlash, Validate, ValidationErrors};
+        use crate::app::RequestContext;
+        use crate::components::{HASHIRA_ACTION_HEADER, HASHIRA_CSRF_COOKIE, HASHIRA_CSRF_HEADER};
+        use crate::web::{Form, FromRequest, Valid};
+        use http::header;
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        struct NewTodo {
+            title: String,
+        }
+
+        impl Validate for NewTodo {
+            fn validate(&self) -> Result<(), ValidationErrors> {
+                let mut errors = ValidationErrors::new();
+                if self.title.is_empty() {
+                    errors.add("title", "title is required");
+                }
+                errors.into_result()
+            }
+        }
+
+        struct AddTodo;
+
+        impl Action for AddTodo {
+            type Response = String;
+
+            fn route() -> &'static str {
+                "/_hashira/action/add_todo"
+            }
+
+            fn call(
+                ctx: RequestContext,
+                mut body: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async move {
+                    let Valid(Form(todo)) =
+                        Valid::<Form<NewTodo>>::from_request(&ctx, &mut body).await?;
+                    Ok(todo.title)
+                })
+            }
+        }
+
+        let service = App::<Base>::new().action::<AddTodo>().build();
+
+        // The client receives the errors as json
+        let req = Request::post(AddTodo::route())
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(HASHIRA_ACTION_HEADER, "true")
+            .header(HASHIRA_CSRF_HEADER, "token")
+            .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}=token"))
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::from("title=")).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let errors = serde_json::from_slice::<ValidationErrors>(&bytes).unwrap();
+        assert_eq!(errors.get("title"), ["title is required"]);
+
+        // A native form receives the errors in the flash
+        let req = Request::post(AddTodo::route())
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}=token"))
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::from("title=&_csrf=token")).await;
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+
+        let set_cookie = res.headers().get(header::SET_COOKIE).unwrap();
+        let cookie = cookie::Cookie::parse_encoded(set_cookie.to_str().unwrap()).unwrap();
+        let flash = serde_json::from_str::<ActionFlash>(cookie.value()).unwrap();
+        let err = flash.result_for::<AddTodo>().unwrap().unwrap_err();
+        assert_eq!(err.downcast_ref::<ValidationErrors>(), Some(&errors));
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn csrf_action_test() {
+        use crate::actions::Action;
+        use crate::app::RequestContext;
+        use crate::components::{
+            HASHIRA_CSRF_COOKIE, HASHIRA_CSRF_HEADER, HASHIRA_PAGE_DATA_HEADER,
+        };
+        use http::header;
+
+        struct DeleteTodo;
+
+        impl Action for DeleteTodo {
+            type Response = ();
+
+            fn route() -> &'static str {
+                "/_hashira/action/delete_todo"
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        struct Webhook;
+
+        impl Action for Webhook {
+            type Response = ();
+
+            fn route() -> &'static str {
+                "/_hashira/action/webhook"
+            }
+
+            fn csrf_protection() -> bool {
+                false
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        #[function_component]
+        fn TodosPage() -> yew::Html {
+            yew::html! { "todos" }
+        }
+
+        crate::impl_page_component!(TodosPage, "/todos");
+
+        let service = App::<Base>::new()
+            .page::<TodosPage>()
+            .action::<DeleteTodo>()
+            .action::<Webhook>()
+            .build();
+
+        // The page sends a new token to the client
+        let req = Request::builder()
+            .uri("/todos")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        let set_cookie = res.headers().get(header::SET_COOKIE).unwrap().clone();
+        let cookie = cookie::Cookie::parse(set_cookie.to_str().unwrap().to_owned()).unwrap();
+        assert_eq!(cookie.name(), HASHIRA_CSRF_COOKIE);
+
+        let token = cookie.value().to_owned();
+
+        let send = |token: Option<&str>, route: &str| {
+            let mut builder = Request::post(route)
+                .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}={}", cookie.value()));
+
+            if let Some(token) = token {
+                builder = builder.header(HASHIRA_CSRF_HEADER, token);
+            }
+
+            let req = builder.body(()).unwrap();
+            service.handle_request(req, Body::empty())
+        };
+
+        let res = send(None, DeleteTodo::route()).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = send(Some("other"), DeleteTodo::route()).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = send(Some(&token), DeleteTodo::route()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // The action opt-out of the verification
+        let res = send(None, Webhook::route()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn action_guard_test() {
+        use crate::actions::Action;
+        use crate::app::RequestContext;
+        use crate::error::ServerError;
+
+        async fn is_admin(ctx: RequestContext) -> Result<(), ServerError> {
+            match ctx.request().headers().get("x-role") {
+                Some(role) if role == "admin" => Ok(()),
+                _ => Err(ServerError::from_status(StatusCode::FORBIDDEN)),
+            }
+        }
+
+        async fn is_user(ctx: RequestContext) -> Result<(), ServerError> {
+            match ctx.request().headers().get("x-role") {
+                Some(_) => Ok(()),
+                None => Err(ServerError::from_status(StatusCode::UNAUTHORIZED)),
+            }
+        }
+
+        struct DeleteUser;
+
+        impl Action for DeleteUser {
+            type Response = ();
+
+            fn route() -> &'static str {
+                "/_hashira/action/delete_user"
+            }
+
+            fn csrf_protection() -> bool {
+                false
+            }
+
+            fn guard(ctx: RequestContext) -> crate::types::BoxFuture<Result<(), ServerError>> {
+                Box::pin(is_user(ctx))
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .action_with_guard::<DeleteUser, _>(is_admin)
+            .build();
+
+        let send = |role: Option<&str>| {
+            let mut builder = Request::post(DeleteUser::route());
+
+            if let Some(role) = role {
+                builder = builder.header("x-role", role);
+            }
+
+            service.handle_request(builder.body(()).unwrap(), Body::empty())
+        };
+
+        // The guard of the action runs first
+        let res = send(None).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = send(Some("user")).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = send(Some("admin")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn page_loader_test() {
+        use crate::components::{PageComponent, PageData, HASHIRA_PAGE_DATA_HEADER};
+        use crate::routing::Params;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, yew::Properties, Serialize, Deserialize)]
+        struct UserPageProps {
+            name: String,
+        }
+
+        async fn load_user(params: Params) -> crate::Result<UserPageProps> {
+            let name = params.get("name").unwrap().to_owned();
+            Ok(UserPageProps { name })
+        }
+
+        #[function_component]
+        fn UserPage(props: &UserPageProps) -> yew::Html {
+            yew::html! {
+                <h1>{format!("hello {}", props.name)}</h1>
+            }
+        }
+
+        impl PageComponent for UserPage {
+            fn route() -> Option<&'static str> {
+                Some("/users/:name")
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(crate::components::handler::call_loader::<Self, BASE, _, _, _>(
+                    ctx, body, load_user,
+                ))
+            }
+        }
+
+        let service = App::<Base>::new().page::<UserPage>().build();
+
+        let res = send_request_get_text(&service, "/users/hashira", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.body().contains("hello hashira"), "body: {}", res.body());
+
+        let req = Request::builder()
+            .uri("/users/yew")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let page_data = serde_json::from_slice::<PageData>(&bytes).unwrap();
+        assert_eq!(page_data.props, serde_json::json!({ "name": "yew" }));
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn page_cache_policy_test() {
+        use crate::app::CachePolicy;
+        use crate::components::{PageComponent, HASHIRA_CACHE_TAGS_HEADER};
+        use std::time::Duration;
+
+        #[function_component]
+        fn CompA() -> yew::Html {
+            yew::html! {
+                "test - component (a)"
+            }
+        }
+
+        impl PageComponent for CompA {
+            fn route() -> Option<&'static str> {
+                Some("/a")
+            }
+
+            fn render<BASE>(
+                mut ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                ctx.cache(
+                    CachePolicy::new()
+                        .max_age(Duration::from_secs(60))
+                        .stale_while_revalidate(Duration::from_secs(30))
+                        .tag("todos"),
+                );
+
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new().page::<CompA>().build();
+        let res = service
+            .handle_request(create_req("/a", Method::GET), Body::empty())
+            .await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(http::header::CACHE_CONTROL).unwrap(),
+            "public, max-age=60, stale-while-revalidate=30"
+        );
+        assert_eq!(res.headers().get(HASHIRA_CACHE_TAGS_HEADER).unwrap(), "todos");
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn page_cache_test() {
+        use crate::app::CachePolicy;
+        use crate::components::PageComponent;
+        use crate::server::PageCache;
+        use crate::web::{InvalidateTags, Json};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        static RENDER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        #[function_component]
+        fn CompA() -> yew::Html {
+            yew::html! {
+                "test - component (a)"
+            }
+        }
+
+        impl PageComponent for CompA {
+            fn route() -> Option<&'static str> {
+                Some("/a")
+            }
+
+            fn render<BASE>(
+                mut ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                RENDER_COUNT.fetch_add(1, Ordering::SeqCst);
+                ctx.cache(
+                    CachePolicy::new()
+                        .max_age(Duration::from_secs(60))
+                        .tag("todos"),
+                );
+
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        async fn update_todos() -> InvalidateTags<Json<bool>> {
+            InvalidateTags::new(Json(true)).tag("todos")
+        }
+
+        let mut update_route = Route::post("/update", update_todos);
+        update_route
+            .extensions_mut()
+            .insert(crate::routing::HandlerKind::Action);
+
+        let service = App::<Base>::new()
+            .page::<CompA>()
+            .route(update_route)
+            .page_cache(PageCache::in_memory(10))
+            .build();
+
+        let res1 = send_request_get_text(&service, "/a", "").await;
+        let res2 = send_request_get_text(&service, "/a", "").await;
+        assert_eq!(res1.body(), res2.body());
+        assert_eq!(RENDER_COUNT.load(Ordering::SeqCst), 1);
+
+        let res = service
+            .handle_request(create_req("/update", Method::POST), Body::empty())
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res3 = send_request_get_text(&service, "/a", "").await;
+        assert_eq!(res3.status(), StatusCode::OK);
+        assert_eq!(RENDER_COUNT.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn page_cache_csrf_token_test() {
+        use crate::actions::{use_action, Action};
+        use crate::app::{CachePolicy, RequestContext};
+        use crate::components::{ActionForm, PageComponent, HASHIRA_CSRF_COOKIE};
+        use crate::server::PageCache;
+        use http::header;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        static RENDER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        struct AddTodo;
+
+        impl Action for AddTodo {
+            type Response = ();
+
+            fn route() -> &'static str {
+                "/_hashira/action/add_todo"
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        #[function_component]
+        fn TodosPage() -> yew::Html {
+            let action = use_action();
+            yew::html! {
+                <ActionForm<AddTodo> action={action}>{"add todo"}</ActionForm<AddTodo>>
+            }
+        }
+
+        impl PageComponent for TodosPage {
+            fn route() -> Option<&'static str> {
+                Some("/todos")
+            }
+
+            fn render<BASE>(
+                mut ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                RENDER_COUNT.fetch_add(1, Ordering::SeqCst);
+                ctx.cache(CachePolicy::new().max_age(Duration::from_secs(60)));
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .page::<TodosPage>()
+            .action::<AddTodo>()
+            .page_cache(PageCache::in_memory(10))
+            .build();
+
+        let send = |token: &str| {
+            let req = Request::get("/todos")
+                .header(header::COOKIE, format!("{HASHIRA_CSRF_COOKIE}={token}"))
+                .body(())
+                .unwrap();
+
+            service.handle_request(req, Body::empty())
+        };
+
+        // Each client receives a page with its own token
+        for token in ["token-a", "token-b"] {
+            let res = send(token).await;
+            assert_eq!(res.status(), StatusCode::OK);
+
+            let bytes = res.into_body().into_bytes().await.unwrap();
+            let body = String::from_utf8(bytes.to_vec()).unwrap();
+            assert!(body.contains(token), "body: {body}");
+        }
+
+        assert_eq!(RENDER_COUNT.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn middleware_layer_cached_page_test() {
+        use crate::app::{nested, CachePolicy, Next, RequestContext};
+        use crate::components::PageComponent;
+        use crate::server::{PageCache, StaticRegeneration};
+        use crate::web::IntoResponse;
+        use http::{header, HeaderValue};
+        use std::time::Duration;
+
+        async fn trace(ctx: RequestContext, body: Body, next: Next) -> Response {
+            let mut res = next.run(ctx, body).await;
+            res.headers_mut()
+                .insert("x-layers", HeaderValue::from_static("app"));
+            res
+        }
+
+        async fn require_token(ctx: RequestContext, body: Body, next: Next) -> Response {
+            if !ctx.request().headers().contains_key("x-token") {
+                return StatusCode::UNAUTHORIZED.into_response();
+            }
+
+            next.run(ctx, body).await
+        }
+
+        #[function_component]
+        fn CachedPage() -> yew::Html {
+            yew::html! {
+                "test - cached page"
+            }
+        }
+
+        #[function_component]
+        fn StoredPage() -> yew::Html {
+            yew::html! {
+                "test - stored page"
+            }
+        }
+
+        impl PageComponent for CachedPage {
+            fn route() -> Option<&'static str> {
+                Some("/cached")
+            }
+
+            fn render<BASE>(
+                mut ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                ctx.cache(CachePolicy::new().max_age(Duration::from_secs(60)));
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        impl PageComponent for StoredPage {
+            fn route() -> Option<&'static str> {
+                Some("/stored")
+            }
+
+            fn revalidate() -> Option<Duration> {
+                Some(Duration::from_secs(60))
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .layer(trace)
+            .nest(
+                "/admin",
+                nested()
+                    .layer(require_token)
+                    .page::<CachedPage>()
+                    .page::<StoredPage>(),
+            )
+            .route(Route::delete("/items", noop))
+            .page_cache(PageCache::in_memory(10))
+            .static_regeneration(StaticRegeneration::in_memory(10))
+            .build();
+
+        let send = |path: &str, token: Option<&str>| {
+            let mut req = Request::get(path);
+            if let Some(token) = token {
+                req = req.header("x-token", token);
+            }
+
+            service.handle_request(req.body(()).unwrap(), Body::empty())
+        };
+
+        // The pages are cached and stored by the authorized requests,
+        // but the middlewares still run before serving them
+        for path in ["/admin/cached", "/admin/stored"] {
+            for _ in 0..2 {
+                let res = send(path, Some("secret")).await;
+                assert_eq!(res.status(), StatusCode::OK);
+                assert_eq!(res.headers()["x-layers"], "app");
+            }
+
+            let res = send(path, None).await;
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // The automatic responses also run the middlewares of the app
+        let res = service
+            .handle_request(create_req("/items", Method::OPTIONS), Body::empty())
+            .await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(res.headers()["x-layers"], "app");
+        assert_eq!(res.headers()[header::ALLOW], "DELETE, OPTIONS");
+
+        let res = send("/items", None).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[header::ALLOW], "DELETE, OPTIONS");
+    }
+
+    #[tokio::test]
+    async fn static_regeneration_test() {
+        use crate::components::PageComponent;
+        use crate::server::StaticRegeneration;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        static FRESH_RENDER_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static STALE_RENDER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        #[function_component]
+        fn FreshPage() -> yew::Html {
+            yew::html! {
+                "test - fresh page"
+            }
+        }
+
+        #[function_component]
+        fn StalePage() -> yew::Html {
+            yew::html! {
+                "test - stale page"
+            }
+        }
+
+        impl PageComponent for FreshPage {
+            fn route() -> Option<&'static str> {
+                Some("/fresh")
+            }
+
+            fn revalidate() -> Option<Duration> {
+                Some(Duration::from_secs(60))
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                FRESH_RENDER_COUNT.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        impl PageComponent for StalePage {
+            fn route() -> Option<&'static str> {
+                Some("/stale")
+            }
+
+            fn revalidate() -> Option<Duration> {
+                Some(Duration::ZERO)
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                _body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                STALE_RENDER_COUNT.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .page::<FreshPage>()
+            .page::<StalePage>()
+            .static_regeneration(StaticRegeneration::in_memory(10))
+            .build();
+
+        let res1 = send_request_get_text(&service, "/fresh", "").await;
+        let res2 = send_request_get_text(&service, "/fresh", "").await;
+        assert_eq!(res1.body(), res2.body());
+        assert_eq!(FRESH_RENDER_COUNT.load(Ordering::SeqCst), 1);
+
+        // The query string is not part of the stored page
+        let res3 = send_request_get_text(&service, "/fresh?x=1", "").await;
+        assert_eq!(res1.body(), res3.body());
+        assert_eq!(FRESH_RENDER_COUNT.load(Ordering::SeqCst), 1);
+
+        // The stale page is served and regenerated in the background
+        let res1 = send_request_get_text(&service, "/stale", "").await;
+        let res2 = send_request_get_text(&service, "/stale", "").await;
+        assert_eq!(res1.body(), res2.body());
+        assert_eq!(STALE_RENDER_COUNT.load(Ordering::SeqCst), 1);
+
+        // The stored page is read and written outside the runtime
+        for _ in 0..100 {
+            if STALE_RENDER_COUNT.load(Ordering::SeqCst) == 2 {
+                break;
+            }
+
+            tokio::task::yield_now().await;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(STALE_RENDER_COUNT.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn streaming_suspense_test() {
+        use crate::components::StreamingSuspense;
+        use yew::{suspense::use_future, HtmlResult};
+
+        #[function_component]
+        fn SlowContent() -> HtmlResult {
+            let value = use_future(|| async { "slow content" })?;
+            Ok(yew::html! {
+                <p>{*value}</p>
+            })
+        }
+
+        #[function_component]
+        fn StreamPage() -> yew::Html {
+            yew::html! {
+                <div>
+                    <StreamingSuspense fallback={yew::html! { "loading..." }}>
+                        <SlowContent/>
+                    </StreamingSuspense>
+                    <p>{"after boundary"}</p>
+                </div>
+            }
+        }
+
+        crate::impl_page_component!(StreamPage, "/stream");
+
+        let service = App::<Base>::new().page::<StreamPage>().build();
+        let res = send_request_get_text(&service, "/stream", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // The fallback is sent in place and the content at the end
+        let body = res.body();
+        let fallback = body.find("loading...").unwrap();
+        let after = body.find("after boundary").unwrap();
+        let resolved = body.find("slow content").unwrap();
+
+        assert!(body.contains(r#"<hashira-suspense id="hashira-suspense-0">"#));
+        assert!(body.contains(r#"<template id="hashira-suspense-0-template">"#));
+        assert!(fallback < after && after < resolved, "body: {body}");
+
+        // The template only contains the `<Suspense>` the client hydrates
+        let template = r#"<template id="hashira-suspense-0-template">"#;
+        let start = body.find(template).unwrap() + template.len();
+        let end = start + body[start..].find("</template>").unwrap();
+        let content = &body[start..end];
+        let open_marker = &content[..content.find("]>-->").unwrap()];
+
+        assert!(
+            open_marker.starts_with("<!--<[") && open_marker.ends_with("Suspense"),
+            "content: {content}"
+        );
+        assert!(content.ends_with("Suspense]>-->"), "content: {content}");
+    }
+
+    #[tokio::test]
+    async fn streaming_suspense_failed_boundary_test() {
+        use crate::components::StreamingSuspense;
+
+        #[function_component]
+        fn FailingContent() -> yew::Html {
+            panic!("failed to render content");
+        }
+
+        #[function_component]
+        fn FailingStreamPage() -> yew::Html {
+            yew::html! {
+                <div>
+                    <StreamingSuspense fallback={yew::html! { "loading..." }}>
+                        <FailingContent/>
+                    </StreamingSuspense>
+                    <p>{"after boundary"}</p>
+                </div>
+            }
+        }
+
+        crate::impl_page_component!(FailingStreamPage, "/failing-stream");
+
+        // The stream ends keeping the fallback
+        let service = App::<Base>::new().page::<FailingStreamPage>().build();
+        let res = send_request_get_text(&service, "/failing-stream", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = res.body();
+        assert!(body.contains("loading...") && body.contains("after boundary"));
+        assert!(!body.contains("hashira-suspense-0-template"), "body: {body}");
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn deferred_page_data_test() {
+        use crate::components::{
+            Deferred, PageComponent, PageData, StreamingSuspense, HASHIRA_PAGE_DATA_HEADER,
+        };
+        use crate::hooks::use_deferred;
+        use serde::{Deserialize, Serialize};
+        use yew::HtmlResult;
+
+        #[derive(Debug, PartialEq, yew::Properties, Serialize, Deserialize)]
+        struct PostsPageProps {
+            title: String,
+            posts: Deferred<Vec<String>>,
+        }
+
+        async fn load_posts() -> crate::Result<PostsPageProps> {
+            let posts = Deferred::new(async {
+                tokio::task::yield_now().await;
+                vec!["first post".to_owned()]
+            });
+
+            Ok(PostsPageProps {
+                title: "My posts".to_owned(),
+                posts,
+            })
+        }
+
+        #[derive(PartialEq, yew::Properties)]
+        struct PostListProps {
+            posts: Deferred<Vec<String>>,
+        }
+
+        #[function_component]
+        fn PostList(props: &PostListProps) -> HtmlResult {
+            let posts = use_deferred(&props.posts)?.unwrap_or_default();
+            Ok(yew::html! {
+                <ul>{for posts.iter().map(|p| yew::html! { <li>{p}</li> })}</ul>
+            })
+        }
+
+        #[function_component]
+        fn PostsPage(props: &PostsPageProps) -> yew::Html {
+            yew::html! {
+                <div>
+                    <h1>{&props.title}</h1>
+                    <StreamingSuspense fallback={yew::html! { "loading posts..." }}>
+                        <PostList posts={props.posts.clone()}/>
+                    </StreamingSuspense>
+                </div>
+            }
+        }
+
+        impl PageComponent for PostsPage {
+            fn route() -> Option<&'static str> {
+                Some("/posts")
+            }
+
+            fn render<BASE>(
+                ctx: crate::app::RenderContext,
+                body: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(crate::components::handler::call_loader::<Self, BASE, _, _, _>(
+                    ctx, body, load_posts,
+                ))
+            }
+        }
+
+        let service = App::<Base>::new().page::<PostsPage>().build();
+
+        // The page is sent with a placeholder and the value streamed after
+        let res = send_request_get_text(&service, "/posts", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = res.body();
+        let fallback = body.find("loading posts...").unwrap();
+        let resolved = body.find("<li>first post</li>").unwrap();
+        assert!(fallback < resolved, "body: {body}");
+        assert!(body.contains(r#"{"__hashira_deferred":0}"#));
+        assert!(body.contains(
+            r#"<script type="application/json" id="hashira-deferred-0">["first post"]</script>"#
+        ));
+
+        // The client router receives the value already resolved
+        let req = Request::builder()
+            .uri("/posts")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let page_data = serde_json::from_slice::<PageData>(&bytes).unwrap();
+        assert_eq!(
+            page_data.props["posts"],
+            serde_json::json!({ "__hashira_deferred": 0, "value": ["first post"] })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_page_route_test_1() {
+        #[function_component]
+        fn CompA() -> yew::Html {
+            yew::html! {
+                "test - component (a)"
+            }
+        }
+
+        crate::impl_page_component!(CompA, "");
+        let _ = App::<Base>::new().page::<CompA>().build();
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_page_route_test_2() {
+        #[function_component]
+        fn CompA() -> yew::Html {
+            yew::html! {
+                "test - component (a)"
+            }
+        }
+
+        crate::impl_page_component!(CompA, "/a/");
+        let _ = App::<Base>::new().page::<CompA>().build();
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicated_error_handler_test() {
+        #[function_component]
+        fn ErrorPage1() -> yew::Html {
+            yew::html! {
+                "test - not found"
+            }
+        }
+
+        #[function_component]
+        fn ErrorPage2() -> yew::Html {
+            yew::html! {
+                "test - not found"
+            }
+        }
+
+        crate::impl_page_component!(ErrorPage1);
+        crate::impl_page_component!(ErrorPage2);
+
+        let _ = App::<Base>::new()
+            .error_page::<ErrorPage1>(StatusCode::NOT_FOUND)
+            .error_page::<ErrorPage2>(StatusCode::NOT_FOUND)
+            .build();
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "client"))]
+    async fn query_data_test() {
+        use crate::actions::{query_key, use_query, Action};
+        use crate::app::RequestContext;
+        use crate::components::{PageComponent, PageData, HASHIRA_PAGE_DATA_HEADER};
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        struct TodoFilter {
+            done: bool,
+        }
+
+        struct GetTodos;
+
+        impl Action for GetTodos {
+            type Response = crate::web::Json<Vec<String>>;
+
+            fn route() -> &'static str {
+                "/_hashira/action/get_todos"
+            }
+
+            fn call(
+                _: RequestContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<crate::Result<Self::Response>> {
+                Box::pin(async { Ok(crate::web::Json(vec![])) })
+            }
+        }
+
+        #[function_component]
+        fn TodosPage() -> yew::Html {
+            let todos = use_query::<GetTodos, _>(TodoFilter { done: true });
+            let todos = todos.data().cloned().unwrap_or_default();
+
+            yew::html! {
+                {for todos.iter().map(|todo| yew::html! { <li>{todo}</li> })}
+            }
+        }
+
+        impl PageComponent for TodosPage {
+            fn route() -> Option<&'static str> {
+                Some("/todos")
+            }
+
+            fn render<BASE>(
+                mut ctx: crate::app::RenderContext,
+                _: Body,
+            ) -> crate::types::BoxFuture<Result<Response, crate::error::BoxError>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(async move {
+                    let todos = vec![String::from("Buy milk")];
+                    ctx.query_data::<GetTodos, _>(&TodoFilter { done: true }, &todos)?;
+                    Ok(ctx.render::<Self, BASE>().await)
+                })
+            }
+        }
+
+        let service = App::<Base>::new().page::<TodosPage>().build();
+
+        // The page is rendered with the data of the query
+        let res = send_request_get_text(&service, "/todos", "").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.body().contains("<li>Buy milk</li>"), "body: {}", res.body());
+
+        let req = Request::builder()
+            .uri("/todos")
+            .header(HASHIRA_PAGE_DATA_HEADER, "true")
+            .body(())
+            .unwrap();
+
+        let res = service.handle_request(req, Body::empty()).await;
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        let page_data = serde_json::from_slice::<PageData>(&bytes).unwrap();
+
+        let key = query_key::<GetTodos, _>(&TodoFilter { done: true });
+        assert_eq!(key, "/_hashira/action/get_todos?done=true");
+        assert_eq!(
+            page_data.queries.get(&key),
+            Some(&serde_json::json!(["Buy milk"]))
+        );
     }
 
     #[test]
@@ -0,0 +1,229 @@
+use crate::components::HASHIRA_CACHE_TAGS_HEADER;
+use http::{header, HeaderMap, HeaderValue};
+use std::time::Duration;
+
+/// Describes for how long the response of a page can be reused.
+///
+/// This is sent as a `Cache-Control` header and it's also used by the client router
+/// to reuse the page data when navigating.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    max_age: Option<Duration>,
+    stale_while_revalidate: Option<Duration>,
+    tags: Vec<String>,
+    private: bool,
+}
+
+impl CachePolicy {
+    /// Constructs an empty cache policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the time the response is considered fresh.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets the time an stale response can be used while is revalidated in the background.
+    pub fn stale_while_revalidate(mut self, duration: Duration) -> Self {
+        self.stale_while_revalidate = Some(duration);
+        self
+    }
+
+    /// Adds a tag to this response, tags can be invalidated by actions.
+    ///
+    /// Tags must be non-empty visible ASCII without commas, invalid tags are ignored.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if is_valid_cache_tag(&tag) {
+            self.tags.push(tag);
+        } else {
+            log::warn!("ignoring invalid cache tag: {tag:?}");
+        }
+
+        self
+    }
+
+    /// Whether if the response is only for the current user and should not be stored in shared caches.
+    pub fn private(mut self, private: bool) -> Self {
+        self.private = private;
+        self
+    }
+
+    /// Returns the time the response is considered fresh.
+    pub fn get_max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Returns the time an stale response can be used.
+    pub fn get_stale_while_revalidate(&self) -> Option<Duration> {
+        self.stale_while_revalidate
+    }
+
+    /// Returns `true` if the response is only for the current user.
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    /// Returns the tags of the response.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns `true` if the response can be reused.
+    pub fn is_cacheable(&self) -> bool {
+        self.max_age.is_some() || self.stale_while_revalidate.is_some()
+    }
+
+    /// Returns the value of the `Cache-Control` header for this policy.
+    pub fn cache_control(&self) -> String {
+        let mut directives = vec![];
+
+        directives.push(if self.private { "private" } else { "public" }.to_owned());
+
+        match self.max_age {
+            Some(max_age) => directives.push(format!("max-age={}", max_age.as_secs())),
+            None => directives.push("no-cache".to_owned()),
+        }
+
+        if let Some(swr) = self.stale_while_revalidate {
+            directives.push(format!("stale-while-revalidate={}", swr.as_secs()));
+        }
+
+        directives.join(", ")
+    }
+
+    /// Sets the `Cache-Control` and cache tags headers.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        let cache_control = HeaderValue::try_from(self.cache_control()).unwrap();
+        headers.insert(header::CACHE_CONTROL, cache_control);
+
+        if let Some(tags) = tags_header_value(&self.tags) {
+            headers.insert(HASHIRA_CACHE_TAGS_HEADER, tags);
+        }
+    }
+
+    /// Parses a cache policy from the `Cache-Control` and cache tags header values.
+    ///
+    /// Returns `None` if the response must not be stored.
+    pub fn parse(cache_control: &str, tags: Option<&str>) -> Option<Self> {
+        let mut policy = CachePolicy::new();
+
+        for directive in cache_control.split(',').map(str::trim) {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim())),
+                None => (directive, None),
+            };
+
+            let secs = || {
+                value
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(Duration::from_secs)
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => return None,
+                "private" => policy.private = true,
+                "max-age" => policy.max_age = secs(),
+                "stale-while-revalidate" => policy.stale_while_revalidate = secs(),
+                _ => {}
+            }
+        }
+
+        if let Some(tags) = tags {
+            policy.tags = tags
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        Some(policy)
+    }
+
+    /// Parses a cache policy from the given response headers.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let cache_control = headers.get(header::CACHE_CONTROL)?.to_str().ok()?;
+        let tags = headers
+            .get(HASHIRA_CACHE_TAGS_HEADER)
+            .and_then(|x| x.to_str().ok());
+
+        CachePolicy::parse(cache_control, tags)
+    }
+}
+
+/// Returns `true` if the tag can be sent in a header.
+pub(crate) fn is_valid_cache_tag(tag: &str) -> bool {
+    !tag.is_empty() && tag.bytes().all(|b| b.is_ascii_graphic() && b != b',')
+}
+
+/// Returns the header value with the given tags, or `None` if there are no valid tags.
+pub(crate) fn tags_header_value(tags: &[String]) -> Option<HeaderValue> {
+    let tags = tags
+        .iter()
+        .map(String::as_str)
+        .filter(|tag| is_valid_cache_tag(tag))
+        .collect::<Vec<_>>();
+
+    if tags.is_empty() {
+        return None;
+    }
+
+    HeaderValue::try_from(tags.join(",")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CachePolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn cache_control_test() {
+        let policy = CachePolicy::new()
+            .max_age(Duration::from_secs(60))
+            .stale_while_revalidate(Duration::from_secs(30));
+
+        assert_eq!(
+            policy.cache_control(),
+            "public, max-age=60, stale-while-revalidate=30"
+        );
+
+        let policy = CachePolicy::new().private(true);
+        assert_eq!(policy.cache_control(), "private, no-cache");
+    }
+
+    #[test]
+    fn parse_test() {
+        let policy = CachePolicy::parse(
+            "public, max-age=60, stale-while-revalidate=30",
+            Some("todos, users"),
+        )
+        .unwrap();
+
+        assert_eq!(policy.get_max_age(), Some(Duration::from_secs(60)));
+        assert_eq!(
+            policy.get_stale_while_revalidate(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(policy.tags(), &["todos", "users"]);
+        assert!(policy.is_cacheable());
+
+        assert!(CachePolicy::parse("no-store", None).is_none());
+        assert!(!CachePolicy::parse("no-cache", None).unwrap().is_cacheable());
+    }
+
+    #[test]
+    fn invalid_tag_test() {
+        let policy = CachePolicy::new()
+            .tag("a,b")
+            .tag("")
+            .tag("todo\n")
+            .tag("tódos")
+            .tag("todos");
+
+        assert_eq!(policy.tags(), &["todos"]);
+    }
+}
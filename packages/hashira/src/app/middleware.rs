@@ -0,0 +1,90 @@
+use super::RequestContext;
+use crate::{
+    types::BoxFuture,
+    web::{Body, Response},
+};
+use futures::Future;
+use std::sync::Arc;
+
+/// Resolves the request with the next middleware or the route handler.
+pub struct Next(Box<dyn FnOnce(RequestContext, Body) -> BoxFuture<Response> + Send>);
+
+impl Next {
+    pub(crate) fn new<F>(f: F) -> Self
+    where
+        F: FnOnce(RequestContext, Body) -> BoxFuture<Response> + Send + 'static,
+    {
+        Next(Box::new(f))
+    }
+
+    /// Runs the rest of the chain and returns the response.
+    pub async fn run(self, ctx: RequestContext, body: Body) -> Response {
+        (self.0)(ctx, body).await
+    }
+}
+
+/// A middleware that wraps the handlers of the routes of a scope.
+///
+/// Can be attached with `App::layer`, `AppNested::layer` and `Route::layer`,
+/// the middlewares of a scope run in the order they were added,
+/// and the middlewares of the outer scopes run first.
+///
+/// The middlewares also run for the pages served from the page cache or the static store,
+/// and the middlewares of the app run for the automatic `OPTIONS`, `404` and `405` responses.
+///
+/// # Example
+/// ```rust,ignore
+/// async fn require_admin(ctx: RequestContext, body: Body, next: Next) -> Response {
+///     if !is_admin(ctx.request()) {
+///         return StatusCode::UNAUTHORIZED.into_response();
+///     }
+///
+///     next.run(ctx, body).await
+/// }
+///
+/// App::<Root>::new()
+///     .page::<HomePage>()
+///     .nest("/admin", nested().layer(require_admin).page::<DashboardPage>())
+///     .build();
+/// ```
+#[async_trait::async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    /// Handles the request, calling `next` to continue with the chain.
+    async fn call(&self, ctx: RequestContext, body: Body, next: Next) -> Response;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> Middleware for F
+where
+    F: Fn(RequestContext, Body, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Response> + Send + 'static,
+{
+    async fn call(&self, ctx: RequestContext, body: Body, next: Next) -> Response {
+        (self)(ctx, body, next).await
+    }
+}
+
+/// A shared middleware.
+pub(crate) type BoxMiddleware = Arc<dyn Middleware>;
+
+/// Runs the given middlewares around the function, the first middleware is the outermost.
+pub(crate) fn run_layers<F>(
+    layers: &[BoxMiddleware],
+    ctx: RequestContext,
+    body: Body,
+    f: F,
+) -> BoxFuture<Response>
+where
+    F: FnOnce(RequestContext, Body) -> BoxFuture<Response> + Send + 'static,
+{
+    if layers.is_empty() {
+        return f(ctx, body);
+    }
+
+    let next = layers.iter().rev().fold(Next::new(f), |next, middleware| {
+        let middleware = middleware.clone();
+        Next::new(move |ctx, body| Box::pin(async move { middleware.call(ctx, body, next).await }))
+    });
+
+    Box::pin(next.run(ctx, body))
+}
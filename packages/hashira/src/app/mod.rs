@@ -3,9 +3,11 @@ mod app;
 mod app_data;
 mod app_nested;
 mod app_service;
+mod cache_policy;
 mod default_headers;
 mod handler;
 mod layout_context;
+mod middleware;
 mod render_context;
 mod request_context;
 
@@ -13,9 +15,11 @@ pub use app::*;
 pub use app_data::*;
 pub use app_nested::*;
 pub use app_service::*;
+pub use cache_policy::*;
 pub use default_headers::*;
 pub use handler::*;
 pub use layout_context::*;
+pub use middleware::*;
 pub use render_context::*;
 pub use request_context::*;
 
@@ -1,10 +1,11 @@
-use std::ops::Deref;
+use std::{collections::HashMap, ops::Deref};
 
-use super::RenderLayout;
+use super::{CachePolicy, RenderLayout};
 use super::{page_head::PageHead, RequestContext};
+use crate::actions::{query_key, Action};
 use crate::components::PageComponent;
 use crate::error::{BoxError, ServerError};
-use crate::web::{IntoResponse, Redirect};
+use crate::web::{IntoJsonResponse, IntoResponse, Redirect};
 use crate::{
     server::{Metadata, PageLinks, PageScripts},
     web::Response,
@@ -24,6 +25,8 @@ macro_rules! server_only {
 pub struct RenderContext {
     context: RequestContext,
     head: PageHead,
+    cache_policy: Option<CachePolicy>,
+    queries: HashMap<String, serde_json::Value>,
 
     #[allow(dead_code)]
     render_layout: RenderLayout,
@@ -41,6 +44,8 @@ impl RenderContext {
             render_layout,
             context,
             head,
+            cache_policy: None,
+            queries: HashMap::new(),
         }
     }
 }
@@ -65,6 +70,32 @@ impl RenderContext {
     pub fn scripts(&mut self, scripts: PageScripts) {
         self.head.scripts(scripts);
     }
+
+    /// Sets the cache policy of the rendered page.
+    ///
+    /// The policy is sent in the `Cache-Control` header of the response and
+    /// is used by the client router to reuse the page data.
+    pub fn cache(&mut self, policy: CachePolicy) {
+        self.cache_policy = Some(policy);
+    }
+
+    /// Adds the data of a query to the page.
+    ///
+    /// `use_query` renders the page with this data and the client doesn't fetch it again.
+    pub fn query_data<A, Q>(
+        &mut self,
+        input: &Q,
+        data: &<A::Response as IntoJsonResponse>::Data,
+    ) -> Result<(), BoxError>
+    where
+        A: Action,
+        Q: Serialize,
+    {
+        let key = query_key::<A, Q>(input);
+        let value = serde_json::to_value(data)?;
+        self.queries.insert(key, value);
+        Ok(())
+    }
 }
 
 impl RenderContext {
@@ -96,13 +127,8 @@ impl RenderContext {
         COMP: PageComponent,
         COMP::Properties: Default + Serialize + Send,
     {
-        use crate::web::Html;
-
-        // Return a text/html response
-        match self.render_html::<COMP, BASE>().await {
-            Ok(html) => Html(html).into_response(),
-            Err(err) => ServerError::from_error(err).into_response(),
-        }
+        self.render_with_props::<COMP, BASE>(COMP::Properties::default())
+            .await
     }
 
     /// Render the page with the given props and returns the `text/html` response.
@@ -115,11 +141,20 @@ impl RenderContext {
     {
         use crate::web::Html;
 
+        // The client router only needs the page data
+        #[cfg(not(feature = "client"))]
+        if self.is_page_data_request() {
+            return self.render_page_data::<COMP>(props).await;
+        }
+
         // Return a text/html response
-        match self.render_html_with_props::<COMP, BASE>(props).await {
+        let cache_policy = self.cache_policy.clone();
+        let res = match self.render_html_with_props::<COMP, BASE>(props).await {
             Ok(html) => Html(html).into_response(),
             Err(err) => ServerError::from_error(err).into_response(),
-        }
+        };
+
+        with_cache_policy(res, cache_policy)
     }
 
     /// Render the page and returns the `text/html` response stream.
@@ -129,19 +164,8 @@ impl RenderContext {
         COMP: PageComponent,
         COMP::Properties: Default + Serialize + Send,
     {
-        #[cfg(feature = "client")]
-        server_only!();
-
-        #[cfg(not(feature = "client"))]
-        {
-            use crate::web::StreamResponse;
-
-            // Return a stream text/html response
-            match self.render_html_stream::<COMP, BASE>().await {
-                Ok(stream) => StreamResponse(stream).into_response(),
-                Err(err) => ServerError::from_error(err).into_response(),
-            }
-        }
+        self.render_stream_with_props::<COMP, BASE>(COMP::Properties::default())
+            .await
     }
 
     /// Render the page with the given props and returns the `text/html` response stream.
@@ -159,14 +183,22 @@ impl RenderContext {
         {
             use crate::web::StreamResponse;
 
+            // The client router only needs the page data
+            if self.is_page_data_request() {
+                return self.render_page_data::<COMP>(props).await;
+            }
+
             // Return a stream text/html response
-            match self
+            let cache_policy = self.cache_policy.clone();
+            let res = match self
                 .render_html_stream_with_props::<COMP, BASE>(props)
                 .await
             {
                 Ok(stream) => StreamResponse(stream).into_response(),
                 Err(err) => ServerError::from_error(err).into_response(),
-            }
+            };
+
+            with_cache_policy(res, cache_policy)
         }
     }
 
@@ -250,6 +282,27 @@ impl RenderContext {
         }
     }
 
+    #[cfg(not(feature = "client"))]
+    async fn render_page_data<COMP>(self, props: COMP::Properties) -> Response
+    where
+        COMP: PageComponent,
+        COMP::Properties: Serialize,
+    {
+        use crate::{server::create_page_data, web::Json};
+
+        // Return an application/json response with the page data,
+        // the client router receives the deferred values already resolved
+        let res = match create_page_data::<COMP>(props, &self.context, self.queries).await {
+            Ok((mut page_data, deferred)) => {
+                deferred.resolve_in(&mut page_data.props).await;
+                Json(page_data).into_response()
+            }
+            Err(err) => ServerError::from_error(err).into_response(),
+        };
+
+        with_cache_policy(res, self.cache_policy)
+    }
+
     #[cfg(not(feature = "client"))]
     async fn get_render_options(&self) -> crate::server::RenderPageOptions {
         use crate::{
@@ -316,8 +369,20 @@ impl RenderContext {
             router,
             error_router,
             request_context,
+            queries: self.queries.clone(),
+        }
+    }
+}
+
+// Only successful responses can be cached
+fn with_cache_policy(mut res: Response, cache_policy: Option<CachePolicy>) -> Response {
+    if let Some(cache_policy) = cache_policy {
+        if res.status().is_success() {
+            cache_policy.apply(res.headers_mut());
         }
     }
+
+    res
 }
 
 impl Deref for RenderContext {
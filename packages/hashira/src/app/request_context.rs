@@ -68,6 +68,15 @@ impl RequestContext {
         self.inner.error.as_ref()
     }
 
+    /// Returns `true` if the client is only requesting the `PageData` of the page,
+    /// this is used by the client router to navigate without reloading the page.
+    pub fn is_page_data_request(&self) -> bool {
+        self.inner
+            .request
+            .headers()
+            .contains_key(crate::components::HASHIRA_PAGE_DATA_HEADER)
+    }
+
     /// Returns the the data for the given type.
     pub fn app_data<T>(&self) -> Option<&T>
     where
@@ -0,0 +1,405 @@
+use crate::context::ServerContext;
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt, Stream,
+};
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cell::RefCell, fmt::Debug, future::Future, marker::PhantomData, sync::Arc};
+use thiserror::Error;
+
+// The key used to identify a deferred value in the page props.
+const DEFERRED_KEY: &str = "__hashira_deferred";
+
+type DeferredFuture = Shared<BoxFuture<'static, serde_json::Value>>;
+
+thread_local! {
+    // The deferred values found while serializing the page props.
+    static DEFERRED_COLLECTOR: RefCell<Option<Vec<DeferredFuture>>> = RefCell::new(None);
+}
+
+/// An error reading a `Deferred` value.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DeferredError {
+    /// The value is not part of the current page.
+    #[error("deferred value `{0}` was not found")]
+    NotFound(usize),
+
+    /// The received value is not of the expected type.
+    #[error("failed to deserialize deferred value: {0}")]
+    Deserialize(String),
+}
+
+/// A value of the page props that is sent after the page.
+///
+/// The loader of a page can return props with `Deferred` values for data that
+/// is slow to load, the page is sent without waiting them and each value is streamed
+/// at the end of the page when resolves. Use the `use_deferred` hook to read the value,
+/// preferable inside a `StreamingSuspense` to not block the rest of the page.
+///
+/// # Example
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, Serialize, Deserialize, Properties)]
+/// struct UserPageProps {
+///     name: String,
+///     posts: Deferred<Vec<Post>>,
+/// }
+///
+/// async fn loader(db: Inject<Db>) -> Result<UserPageProps, Error> {
+///     let name = db.get_user_name().await?;
+///     let posts = Deferred::new(async move { db.get_posts().await });
+///     Ok(UserPageProps { name, posts })
+/// }
+/// ```
+pub struct Deferred<T> {
+    state: DeferredState,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Clone)]
+enum DeferredState {
+    // A value created by the loader that was not sent yet
+    Pending(DeferredFuture),
+
+    // A value received in the page props, contains the value when was already resolved
+    Received {
+        id: usize,
+        value: Option<serde_json::Value>,
+    },
+}
+
+impl<T> Deferred<T> {
+    /// Constructs a value that will be resolved by the given future.
+    pub fn new<F>(fut: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Serialize,
+    {
+        let fut = fut
+            .map(|value| match serde_json::to_value(value) {
+                Ok(json) => json,
+                Err(err) => {
+                    log::error!("failed to serialize deferred value: {err}");
+                    serde_json::Value::Null
+                }
+            })
+            .boxed()
+            .shared();
+
+        Deferred {
+            state: DeferredState::Pending(fut),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the id of this value in the current page, if was already sent.
+    pub fn id(&self) -> Option<usize> {
+        match &self.state {
+            DeferredState::Pending(_) => None,
+            DeferredState::Received { id, .. } => Some(*id),
+        }
+    }
+
+    /// Returns the resolved json value, if any.
+    pub(crate) fn resolved_value(&self) -> Option<serde_json::Value> {
+        match &self.state {
+            DeferredState::Pending(fut) => fut.peek().cloned(),
+            DeferredState::Received {
+                value: Some(value), ..
+            } => Some(value.clone()),
+            DeferredState::Received { id, value: None } => {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    crate::utils::wasm::read_deferred_value(*id)
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let _ = id;
+                    None
+                }
+            }
+        }
+    }
+
+    /// Waits for the json value.
+    ///
+    /// On the server the value is taken from the current page, on the client
+    /// is read from the `<script>` streamed by the server.
+    pub(crate) async fn wait(
+        self,
+        server_context: Option<ServerContext>,
+    ) -> Result<serde_json::Value, DeferredError> {
+        let id = match self.state {
+            DeferredState::Pending(fut) => return Ok(fut.await),
+            DeferredState::Received {
+                value: Some(value), ..
+            } => return Ok(value),
+            DeferredState::Received { id, .. } => id,
+        };
+
+        if let Some(deferred) = server_context.as_ref().and_then(|x| x.deferred()) {
+            return deferred.get(id).await.ok_or(DeferredError::NotFound(id));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            Ok(crate::utils::wasm::wait_deferred_value(id).await)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Err(DeferredError::NotFound(id))
+        }
+    }
+}
+
+impl<T> Clone for Deferred<T> {
+    fn clone(&self) -> Self {
+        Deferred {
+            state: self.state.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Deferred<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.state, &other.state) {
+            (DeferredState::Pending(a), DeferredState::Pending(b)) => a.ptr_eq(b),
+            (
+                DeferredState::Received { id: a, value: va },
+                DeferredState::Received { id: b, value: vb },
+            ) => a == b && va == vb,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Debug for Deferred<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Deferred")
+            .field("id", &self.id())
+            .field("value", &self.resolved_value())
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DeferredPlaceholder {
+    #[serde(rename = "__hashira_deferred")]
+    id: usize,
+
+    // A present `null` is a resolved value
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    value: Option<serde_json::Value>,
+}
+
+fn deserialize_some<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    serde_json::Value::deserialize(deserializer).map(Some)
+}
+
+impl<T> Serialize for Deferred<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let placeholder = match &self.state {
+            DeferredState::Pending(fut) => {
+                let id = DEFERRED_COLLECTOR.with(|collector| {
+                    let mut collector = collector.borrow_mut();
+                    let values = collector.as_mut()?;
+                    values.push(fut.clone());
+                    Some(values.len() - 1)
+                });
+
+                let Some(id) = id else {
+                    return Err(S::Error::custom(
+                        "`Deferred` values can only be serialized as part of the page props",
+                    ));
+                };
+
+                DeferredPlaceholder { id, value: None }
+            }
+            DeferredState::Received { id, value } => DeferredPlaceholder {
+                id: *id,
+                value: value.clone(),
+            },
+        };
+
+        placeholder.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Deferred<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let DeferredPlaceholder { id, value } = DeferredPlaceholder::deserialize(deserializer)
+            .map_err(|err| D::Error::custom(format!("invalid deferred value: {err}")))?;
+
+        Ok(Deferred {
+            state: DeferredState::Received { id, value },
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// The deferred values of the props of a page.
+#[derive(Clone, Default)]
+pub struct DeferredValues(Arc<Vec<DeferredFuture>>);
+
+impl DeferredValues {
+    /// Runs the given function collecting the `Deferred` values serialized.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, Self) {
+        let prev = DEFERRED_COLLECTOR.with(|c| c.borrow_mut().replace(vec![]));
+        let ret = f();
+        let values = DEFERRED_COLLECTOR.with(|c| std::mem::replace(&mut *c.borrow_mut(), prev));
+        (ret, DeferredValues(Arc::new(values.unwrap_or_default())))
+    }
+
+    /// Returns the number of deferred values.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there is no deferred values.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Waits for the value with the given id.
+    pub(crate) async fn get(&self, id: usize) -> Option<serde_json::Value> {
+        let fut = self.0.get(id)?.clone();
+        Some(fut.await)
+    }
+
+    /// Waits all the values and includes them in the given props.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) async fn resolve_in(&self, props: &mut serde_json::Value) {
+        if self.is_empty() {
+            return;
+        }
+
+        let values = futures::future::join_all(self.0.iter().cloned()).await;
+        insert_resolved_values(props, &values);
+    }
+
+    /// Returns a stream that yields each value as a `<script>` when resolves.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn into_stream(self) -> impl Stream<Item = String> + Send + Sync {
+        self.0
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(id, fut)| fut.map(move |value| render_deferred_chunk(id, &value)))
+            .collect::<futures::stream::FuturesUnordered<_>>()
+    }
+}
+
+// Replaces the placeholders in the props with the resolved values.
+fn insert_resolved_values(json: &mut serde_json::Value, values: &[serde_json::Value]) {
+    match json {
+        serde_json::Value::Object(map) => {
+            let id = map
+                .get(DEFERRED_KEY)
+                .and_then(|id| id.as_u64())
+                .filter(|_| map.len() == 1);
+
+            match id.and_then(|id| values.get(id as usize)) {
+                Some(value) => {
+                    map.insert("value".to_owned(), value.clone());
+                }
+                None => map
+                    .values_mut()
+                    .for_each(|x| insert_resolved_values(x, values)),
+            }
+        }
+        serde_json::Value::Array(array) => array
+            .iter_mut()
+            .for_each(|x| insert_resolved_values(x, values)),
+        _ => {}
+    }
+}
+
+/// Returns the id of the element containing the deferred value.
+pub(crate) fn deferred_element_id(id: usize) -> String {
+    format!("hashira-deferred-{id}")
+}
+
+/// Name of the event dispatched in the `window` when a deferred value is received.
+pub(crate) const HASHIRA_DEFERRED_EVENT: &str = "hashira:deferred";
+
+fn render_deferred_chunk(id: usize, value: &serde_json::Value) -> String {
+    let element_id = deferred_element_id(id);
+
+    // Prevent the json from closing the `<script>`
+    let json = value.to_string().replace("</", "<\\/");
+
+    format!(
+        r#"<script type="application/json" id="{element_id}">{json}</script><script>window.dispatchEvent(new Event("{HASHIRA_DEFERRED_EVENT}"));document.currentScript.remove();</script>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Deferred, DeferredError, DeferredValues};
+    use futures::StreamExt;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Props {
+        name: String,
+        posts: Deferred<Vec<String>>,
+    }
+
+    #[tokio::test]
+    async fn deferred_props_test() {
+        let props = Props {
+            name: "Ayaka".to_owned(),
+            posts: Deferred::new(async { vec!["hello".to_owned()] }),
+        };
+
+        assert!(serde_json::to_value(&props).is_err());
+
+        let (json, deferred) = DeferredValues::collect(|| serde_json::to_value(&props));
+        let mut json = json.unwrap();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(
+            json,
+            serde_json::json!({ "name": "Ayaka", "posts": { "__hashira_deferred": 0 } })
+        );
+
+        let received = serde_json::from_value::<Props>(json.clone()).unwrap();
+        assert_eq!(received.posts.id(), Some(0));
+        assert_eq!(received.posts.resolved_value(), None);
+
+        let chunks = deferred.clone().into_stream().collect::<Vec<_>>().await;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].contains(r#"id="hashira-deferred-0">["hello"]</script>"#));
+
+        deferred.resolve_in(&mut json).await;
+        let received = serde_json::from_value::<Props>(json).unwrap();
+        assert_eq!(
+            received.posts.resolved_value(),
+            Some(serde_json::json!(["hello"]))
+        );
+    }
+
+    #[tokio::test]
+    async fn deferred_not_found_test() {
+        let deferred: Deferred<Vec<String>> =
+            serde_json::from_str(r#"{"__hashira_deferred":3}"#).unwrap();
+
+        assert_eq!(deferred.id(), Some(3));
+        assert_eq!(deferred.wait(None).await, Err(DeferredError::NotFound(3)));
+    }
+}
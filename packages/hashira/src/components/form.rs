@@ -1,10 +1,13 @@
 use http::Method;
+use std::rc::Rc;
 use web_sys::{window, FormData};
 use yew::html::onsubmit::Event;
 use yew::{function_component, AttrValue, Callback, Children, NodeRef, Properties};
-use yew::{Classes, TargetCast};
+use yew::{use_context, Classes, ContextProvider, TargetCast};
 
-use crate::actions::{Action, AnyForm, RequestOptions, UseActionHandle};
+use crate::actions::{Action, AnyForm, RequestOptions, UseActionHandle, ValidationErrors};
+use crate::components::HASHIRA_CSRF_FIELD;
+use crate::context::use_server_context;
 
 #[derive(Properties)]
 pub struct FormProps<A>
@@ -79,6 +82,13 @@ where
 }
 
 /// A form with extra functionality.
+///
+/// The form is sent using the `action` handle, but still works before the wasm loads
+/// or with javascript disabled, the browser submits the form to the action route and
+/// is redirected back to the current page, where the `action` handle contains the result.
+///
+/// If the action rejects the input with `ValidationErrors`, each `FieldError` inside
+/// the form displays the errors of its field.
 #[function_component]
 pub fn ActionForm<A>(props: &FormProps<A>) -> yew::Html
 where
@@ -96,6 +106,8 @@ where
         props.enc_type.clone()
     };
 
+    let form_errors = FormErrors(action.field_errors().cloned().map(Rc::new));
+    let csrf_token = use_csrf_token();
     let form_enctype = enc_type.clone();
     let on_submit = move |event: yew::html::onsubmit::Event| {
         event.prevent_default();
@@ -142,7 +154,82 @@ where
             action={A::route()}
             enctype={form_enctype}
         >
-            {for props.children.iter()}
+            <input type="hidden" name={HASHIRA_CSRF_FIELD} value={csrf_token} />
+            <ContextProvider<FormErrors> context={form_errors}>
+                {for props.children.iter()}
+            </ContextProvider<FormErrors>>
         </form>
     }
 }
+
+// Returns the CSRF token sent by the form when is submitted by the browser.
+#[yew::hook]
+fn use_csrf_token() -> Option<String> {
+    let server_context = use_server_context();
+
+    // The client reads the token from the cookie, the page could be rendered for other client
+    #[cfg(feature = "client")]
+    {
+        let _ = server_context;
+        crate::actions::CsrfToken::current().map(|token| token.as_str().to_owned())
+    }
+
+    // The page contains the token of the client, so cannot be shared with other clients
+    #[cfg(not(feature = "client"))]
+    {
+        use crate::actions::{CsrfToken, EmbeddedCsrfToken};
+
+        let server_context = server_context?;
+        let req = server_context.request();
+        let token = req.extensions().get::<CsrfToken>()?;
+
+        if let Some(embedded) = req.extensions().get::<EmbeddedCsrfToken>() {
+            embedded.mark();
+        }
+
+        Some(token.as_str().to_owned())
+    }
+}
+
+// The errors of the fields of the form being rendered.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FormErrors(Option<Rc<ValidationErrors>>);
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct FieldErrorProps {
+    /// Name of the field.
+    pub name: AttrValue,
+
+    /// Classes of the list of errors.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// Displays the errors of a field of the parent `ActionForm`, if any.
+///
+/// # Example
+/// ```rust,ignore
+/// <ActionForm<CreateTodo> action={action}>
+///     <input name="title" />
+///     <FieldError name="title" />
+///     <button>Create</button>
+/// </ActionForm<CreateTodo>>
+/// ```
+#[function_component]
+pub fn FieldError(props: &FieldErrorProps) -> yew::Html {
+    let FormErrors(errors) = use_context::<FormErrors>().unwrap_or_default();
+    let messages = errors
+        .as_deref()
+        .map(|errors| errors.get(props.name.as_str()))
+        .unwrap_or_default();
+
+    if messages.is_empty() {
+        return yew::Html::default();
+    }
+
+    yew::html! {
+        <ul class={props.class.clone()} data-field={props.name.clone()}>
+            {for messages.iter().map(|msg| yew::html! { <li>{msg}</li> })}
+        </ul>
+    }
+}
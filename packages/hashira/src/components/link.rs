@@ -0,0 +1,89 @@
+use yew::{function_component, AttrValue, Children, Classes, Properties};
+
+use crate::context::{is_navigation_click, use_navigate, NavigateOptions};
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct LinkProps {
+    /// The url to navigate to, also accepts the `PageUrl` of a page.
+    pub to: AttrValue,
+
+    /// Whether if replace the current history entry instead of pushing a new one.
+    ///
+    /// Defaults to `false`.
+    #[prop_or(false)]
+    pub replace: bool,
+
+    /// Whether if scroll to the top of the page after navigating.
+    ///
+    /// Defaults to `true`.
+    #[prop_or(true)]
+    pub scroll: bool,
+
+    /// Whether if fetch the data of the page when the mouse is over the link.
+    ///
+    /// Defaults to `false`.
+    #[prop_or(false)]
+    pub prefetch: bool,
+
+    /// Id of the link.
+    #[prop_or_default]
+    pub id: Option<AttrValue>,
+
+    /// Classes of the link.
+    #[prop_or_default]
+    pub class: Classes,
+
+    /// Styles of the link.
+    #[prop_or_default]
+    pub style: Option<AttrValue>,
+
+    /// Children of the link.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A link to other page that navigates without reloading the page.
+#[function_component]
+pub fn Link(props: &LinkProps) -> yew::Html {
+    let navigator = use_navigate();
+
+    let onclick = {
+        let navigator = navigator.clone();
+        let to = props.to.clone();
+        let options = NavigateOptions::new()
+            .replace(props.replace)
+            .scroll(props.scroll);
+
+        move |event: yew::MouseEvent| {
+            if !is_navigation_click(&event) {
+                return;
+            }
+
+            event.prevent_default();
+            navigator.navigate(&to, options);
+        }
+    };
+
+    let onmouseenter = {
+        let to = props.to.clone();
+        let prefetch = props.prefetch;
+
+        move |_: yew::MouseEvent| {
+            if prefetch {
+                navigator.prefetch(&to);
+            }
+        }
+    };
+
+    yew::html! {
+        <a href={props.to.clone()}
+            id={props.id.clone()}
+            class={props.class.clone()}
+            style={props.style.clone()}
+            {onclick}
+            {onmouseenter}
+        >
+            {for props.children.iter()}
+        </a>
+    }
+}
@@ -9,6 +9,33 @@ pub const HASHIRA_ROOT: &str = "__hashira__root__";
 pub const HASHIRA_PAGE_DATA: &str = "__hashira__page_data__";
 pub const HASHIRA_WASM_LOADER: &str = "__hashira_wasm_loader";
 
+/// Header sent by the client router to request only the `PageData` of a page.
+pub const HASHIRA_PAGE_DATA_HEADER: &str = "x-hashira-page-data";
+
+/// Name of the file with the `PageData` of an exported page, stored next to its `index.html`.
+pub const HASHIRA_PAGE_DATA_FILE: &str = "page-data.json";
+
+/// Header with the comma separated cache tags of a rendered page.
+pub const HASHIRA_CACHE_TAGS_HEADER: &str = "x-hashira-cache-tags";
+
+/// Header sent by the client when calling an action using `fetch`.
+pub const HASHIRA_ACTION_HEADER: &str = "x-hashira-action";
+
+/// Cookie with the result of an action sent by a native form submission.
+pub const HASHIRA_ACTION_FLASH_COOKIE: &str = "hashira_action_flash";
+
+/// Cookie with the token used to protect the actions against CSRF.
+pub const HASHIRA_CSRF_COOKIE: &str = "hashira_csrf_token";
+
+/// Header with the CSRF token sent by the client when calling an action.
+pub const HASHIRA_CSRF_HEADER: &str = "x-hashira-csrf-token";
+
+/// Name of the form field with the CSRF token sent by a native form submission.
+pub const HASHIRA_CSRF_FIELD: &str = "_csrf";
+
+/// Header with the comma separated cache tags an action invalidates.
+pub const HASHIRA_INVALIDATE_TAGS_HEADER: &str = "x-hashira-invalidate-tags";
+
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct MainProps {
     #[prop_or_default]
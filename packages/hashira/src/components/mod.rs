@@ -1,18 +1,26 @@
 mod any;
+mod deferred;
 mod form;
+mod link;
 mod markers;
 mod page;
 mod page_component;
+mod page_layout;
 mod props_with_children;
 mod root;
+mod streaming_suspense;
 
 pub use any::*;
+pub use deferred::*;
 pub use form::*;
+pub use link::*;
 pub use markers::*;
 pub use page::*;
 pub use page_component::*;
+pub use page_layout::*;
 pub use props_with_children::*;
 pub use root::*;
+pub use streaming_suspense::*;
 
 //
 pub mod error;
@@ -1,5 +1,9 @@
 use super::id::PageId;
-use crate::context::{PageDataContextProvider, ServerContext, ServerContextProvider};
+use crate::context::{
+    use_create_navigator, use_intercept_navigation, PageDataContextProvider,
+    PageNavigatorProvider, ServerContext, ServerContextProvider,
+};
+use crate::actions::ActionFlash;
 use crate::routing::{ErrorRouter, Params};
 use crate::{
     app::router::PageRouterWrapper,
@@ -7,9 +11,9 @@ use crate::{
 };
 use http::{StatusCode, Uri};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use yew::Suspense;
+use std::{collections::HashMap, sync::Arc};
 use yew::{function_component, html::ChildrenProps, BaseComponent, Html, Properties};
+use yew::{use_state, Suspense};
 
 /// The props for the current page.
 #[derive(Clone, PartialEq, Properties)]
@@ -32,13 +36,23 @@ pub fn Page<ROOT>(props: &PageProps) -> Html
 where
     ROOT: BaseComponent<Properties = ChildrenProps>,
 {
-    let props = props.clone();
+    // The page data is swapped by the client router when navigating to other page
+    let page_data = use_state(|| props.page_data.clone());
+    let navigator = use_create_navigator(page_data.clone(), props.router.clone());
+    use_intercept_navigation(&navigator, &page_data);
+
+    let props = PageProps {
+        page_data: (*page_data).clone(),
+        ..props.clone()
+    };
 
     yew::html! {
         <PageDataContextProvider data={props.page_data.clone()}>
-            <ServerContextProvider server_context={props.server_context.clone()}>
-                <PageRouter<ROOT> ..props/>
-            </ServerContextProvider>
+            <PageNavigatorProvider navigator={navigator}>
+                <ServerContextProvider server_context={props.server_context.clone()}>
+                    <PageRouter<ROOT> ..props/>
+                </ServerContextProvider>
+            </PageNavigatorProvider>
         </PageDataContextProvider>
     }
 }
@@ -132,4 +146,12 @@ pub struct PageData {
 
     /// Params of the page, if any.
     pub params: Params,
+
+    /// The result of an action sent by a native form submission, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_flash: Option<ActionFlash>,
+
+    /// The data of the queries added while rendering the page, used by `use_query`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub queries: HashMap<String, serde_json::Value>,
 }
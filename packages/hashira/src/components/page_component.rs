@@ -1,6 +1,7 @@
 use yew::{html::ChildrenProps, BaseComponent};
 
-use crate::{app::RenderContext, error::BoxError, types::BoxFuture, web::{Response, Body}};
+use super::PageLayout;
+use crate::{app::RenderContext, error::BoxError, routing::Params, types::BoxFuture, web::{Response, Body}};
 
 /// Represents a page of a web app.
 pub trait PageComponent: BaseComponent {
@@ -12,6 +13,24 @@ pub trait PageComponent: BaseComponent {
     /// The route of this page.
     fn route() -> Option<&'static str>;
 
+    /// The layout that wraps this page, if any.
+    fn layout() -> Option<PageLayout> {
+        None
+    }
+
+    /// Returns the interval after which the stored render of this page is regenerated,
+    /// the page is only stored if the app uses `StaticRegeneration`.
+    fn revalidate() -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Returns the params used to export a page with a dynamic route, like `/users/:id`.
+    ///
+    /// Each of the returned `Params` is rendered as a separate page by the static export.
+    fn static_params() -> BoxFuture<Result<Vec<Params>, BoxError>> {
+        Box::pin(async { Ok(vec![]) })
+    }
+
     /// A function that renders this page component.
     fn render<BASE>(ctx: RenderContext, body: Body) -> BoxFuture<Result<Response, BoxError>>
     where
@@ -20,11 +39,15 @@ pub trait PageComponent: BaseComponent {
 
 // A handler that renders a page component.
 pub mod handler {
+    use super::PageComponent;
     use crate::{
-        app::RenderContext,
+        app::{Handler, RenderContext},
+        error::BoxError,
         web::{Body, FromRequest, IntoResponse, Response},
     };
     use futures::Future;
+    use serde::Serialize;
+    use yew::{html::ChildrenProps, BaseComponent};
 
     /// Calls the render function of a handler.
     pub async fn call_render<H, Args>(
@@ -45,6 +68,44 @@ pub mod handler {
         Ok(res)
     }
 
+    /// Calls the loader of a page and renders the page with the returned props.
+    ///
+    /// The loader is an async function that can take any `FromRequest` arguments
+    /// and returns the `Properties` of the page. When the client router requests the
+    /// page data the loader runs again and only the props are returned as `json`.
+    pub async fn call_loader<COMP, BASE, H, Args, E>(
+        ctx: RenderContext,
+        mut body: Body,
+        loader: H,
+    ) -> crate::Result<Response>
+    where
+        COMP: PageComponent,
+        COMP::Properties: Serialize + Send,
+        BASE: BaseComponent<Properties = ChildrenProps>,
+        H: Handler<Args, Output = Result<COMP::Properties, E>>,
+        Args: FromRequest,
+        E: Into<BoxError>,
+    {
+        let args = match Args::from_request(&ctx, &mut body).await {
+            Ok(x) => x,
+            Err(err) => return Err(err.into()),
+        };
+
+        let props = loader.call(args).await.map_err(Into::into)?;
+        let res = ctx.render_with_props::<COMP, BASE>(props).await;
+        Ok(res)
+    }
+
+    /// Calls the function that returns the params to export a page.
+    pub async fn call_static_params<F, Fut, E>(f: F) -> crate::Result<Vec<crate::routing::Params>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<crate::routing::Params>, E>>,
+        E: Into<BoxError>,
+    {
+        f().await.map_err(Into::into)
+    }
+
     /// A function that renders a page component.
     pub trait RenderHandler<Args>: Clone + 'static {
         type Output: IntoResponse;
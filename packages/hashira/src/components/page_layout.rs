@@ -0,0 +1,36 @@
+use super::AnyComponent;
+use yew::{
+    html::{ChildrenProps, ChildrenRenderer},
+    BaseComponent, Html,
+};
+
+/// A component that wraps the pages of a route.
+///
+/// Layouts are rendered from outermost to innermost, and because they are
+/// in the same place in the tree, the shared layouts keep its state when
+/// navigating between pages in the client.
+#[derive(Clone, PartialEq)]
+pub struct PageLayout(AnyComponent<Html>);
+
+impl PageLayout {
+    /// Creates a layout from the given component.
+    pub fn of<LAYOUT>() -> Self
+    where
+        LAYOUT: BaseComponent<Properties = ChildrenProps>,
+    {
+        PageLayout(AnyComponent::new(|children: Html| {
+            let props = ChildrenProps {
+                children: ChildrenRenderer::new(vec![children]),
+            };
+
+            yew::html! {
+                <LAYOUT ..props />
+            }
+        }))
+    }
+
+    /// Renders this layout with the given children.
+    pub fn render(&self, children: Html) -> Html {
+        self.0.render_with_props(children)
+    }
+}
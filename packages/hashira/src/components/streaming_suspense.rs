@@ -0,0 +1,316 @@
+use crate::context::{use_server_context, PageDataHandle, PageNavigator, ServerContext};
+use futures::{Stream, StreamExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use yew::{
+    function_component, html::ChildrenRenderer, use_context, use_state, Children, ContextProvider,
+    Html, Properties, Suspense,
+};
+
+// Number of components `BoundaryRoot` renders around the `<Suspense>`, itself and the 3 context providers.
+const BOUNDARY_ROOT_DEPTH: usize = 4;
+
+// Max time the stream waits for the boundaries after the page content was rendered.
+#[cfg_attr(feature = "client", allow(dead_code))]
+const BOUNDARY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Props for the `StreamingSuspense`.
+#[derive(PartialEq, Properties)]
+pub struct StreamingSuspenseProps {
+    /// The content to show while the children are not ready.
+    #[prop_or_default]
+    pub fallback: Html,
+
+    /// The content that may suspend.
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A `<Suspense>` that don't block the page stream while its children are suspended.
+///
+/// When streaming a page the server sends the `fallback` in place and continues
+/// rendering the rest of the page, the children are rendered separately and sent
+/// at the end of the stream with a small inline script that swaps them in.
+/// On the client this behaves as a normal `<Suspense>`.
+///
+/// If the children fail to render or are not ready 30 seconds after the page content,
+/// the stream ends and the `fallback` is kept in place.
+///
+/// # Remarks
+/// When rendered on the server the children only have access to the `hashira` contexts,
+/// like the `PageData` or the `ServerContext`, other contexts provided by the parents are not available.
+#[function_component]
+pub fn StreamingSuspense(props: &StreamingSuspenseProps) -> Html {
+    let server_context = use_server_context();
+    let page_data = use_context::<PageDataHandle>();
+    let navigator = use_context::<PageNavigator>();
+
+    // We only spawn the render of the children once
+    let boundary_id = use_state(|| {
+        let server_context = server_context?;
+        let streamer = server_context.streamer()?.clone();
+        let contexts = BoundaryContexts {
+            page_data: page_data?,
+            navigator: navigator?,
+            server_context,
+        };
+
+        Some(streamer.spawn(contexts, props.children.clone()))
+    });
+
+    match *boundary_id {
+        Some(id) => yew::html! {
+            <hashira-suspense id={boundary_element_id(id)}>
+                {props.fallback.clone()}
+            </hashira-suspense>
+        },
+        None => yew::html! {
+            <hashira-suspense>
+                <Suspense fallback={props.fallback.clone()}>
+                    {props.children.clone()}
+                </Suspense>
+            </hashira-suspense>
+        },
+    }
+}
+
+#[derive(Clone)]
+struct BoundaryContexts {
+    page_data: PageDataHandle,
+    navigator: PageNavigator,
+    server_context: ServerContext,
+}
+
+#[derive(Properties)]
+struct BoundaryRootProps {
+    contexts: BoundaryContexts,
+    children: ChildrenRenderer<Html>,
+}
+
+impl PartialEq for BoundaryRootProps {
+    fn eq(&self, _: &Self) -> bool {
+        false
+    }
+}
+
+// Renders the children of the boundary with the same structure the client will hydrate
+#[function_component]
+fn BoundaryRoot(props: &BoundaryRootProps) -> Html {
+    let BoundaryContexts {
+        page_data,
+        navigator,
+        server_context,
+    } = props.contexts.clone();
+
+    yew::html! {
+        <ContextProvider<PageDataHandle> context={page_data}>
+            <ContextProvider<PageNavigator> context={navigator}>
+                <ContextProvider<ServerContext> context={server_context}>
+                    <Suspense>
+                        {props.children.clone()}
+                    </Suspense>
+                </ContextProvider<ServerContext>>
+            </ContextProvider<PageNavigator>>
+        </ContextProvider<PageDataHandle>>
+    }
+}
+
+/// Renders the `StreamingSuspense` boundaries of a page and collects the resolved chunks.
+#[derive(Clone)]
+pub struct SuspenseStreamer {
+    spawned: Arc<AtomicUsize>,
+    sender: mpsc::UnboundedSender<Option<String>>,
+}
+
+impl SuspenseStreamer {
+    /// Constructs a streamer and the receiver of the resolved chunks,
+    /// a `None` is received for the boundaries that failed to render.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn new() -> (Self, mpsc::UnboundedReceiver<Option<String>>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let streamer = SuspenseStreamer {
+            spawned: Default::default(),
+            sender,
+        };
+
+        (streamer, receiver)
+    }
+
+    // Renders the children in the background and returns the id of the boundary
+    fn spawn(&self, contexts: BoundaryContexts, children: Children) -> usize {
+        let id = self.spawned.fetch_add(1, Ordering::SeqCst);
+        let sender = BoundarySender {
+            id,
+            sender: Some(self.sender.clone()),
+        };
+
+        yew::platform::spawn_local(async move {
+            let props = BoundaryRootProps { contexts, children };
+            let html = yew::LocalServerRenderer::<BoundaryRoot>::with_props(props)
+                .render()
+                .await;
+
+            let content = strip_root_components(&html, BOUNDARY_ROOT_DEPTH);
+            sender.send(render_resolved_chunk(id, content));
+        });
+
+        id
+    }
+
+    /// Returns a stream that yields the resolved boundaries as they complete,
+    /// this must be polled after the page finished rendering.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn into_stream(
+        self,
+        receiver: mpsc::UnboundedReceiver<Option<String>>,
+    ) -> impl Stream<Item = String> + Send + Sync {
+        // Resolved boundaries can also contain other boundaries, so we check the count on each chunk
+        let chunks = futures::stream::unfold(
+            (self, receiver, 0, None),
+            |(this, mut receiver, received, deadline)| async move {
+                let spawned = this.spawned.load(Ordering::SeqCst);
+                if received >= spawned {
+                    return None;
+                }
+
+                // The time starts when the page content was rendered
+                let deadline = deadline.unwrap_or_else(|| Deadline::after(BOUNDARY_TIMEOUT));
+                let Some(chunk) = deadline.recv(&mut receiver).await else {
+                    log::warn!(
+                        "{} streaming suspense boundaries were not resolved in time",
+                        spawned - received
+                    );
+                    return None;
+                };
+
+                Some((chunk, (this, receiver, received + 1, Some(deadline))))
+            },
+        );
+
+        // Failed boundaries keep the fallback
+        chunks.filter_map(futures::future::ready)
+    }
+}
+
+// Sends the resolved chunk of a boundary, or `None` if is dropped before, like when the render panics.
+struct BoundarySender {
+    id: usize,
+    sender: Option<mpsc::UnboundedSender<Option<String>>>,
+}
+
+impl BoundarySender {
+    fn send(mut self, chunk: String) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Some(chunk));
+        }
+    }
+}
+
+impl Drop for BoundarySender {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            log::error!("streaming suspense boundary {} failed to render", self.id);
+            let _ = sender.send(None);
+        }
+    }
+}
+
+// The time limit to receive the resolved boundaries.
+#[derive(Clone, Copy)]
+struct Deadline {
+    #[cfg(not(target_arch = "wasm32"))]
+    instant: tokio::time::Instant,
+}
+
+impl Deadline {
+    fn after(duration: Duration) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Deadline {
+                instant: tokio::time::Instant::now() + duration,
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            // Boundaries are only streamed on the server
+            let _ = duration;
+            Deadline {}
+        }
+    }
+
+    // Receives the next message, returns `None` after the deadline or if the channel was closed
+    async fn recv<T>(&self, receiver: &mut mpsc::UnboundedReceiver<T>) -> Option<T> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            tokio::time::timeout_at(self.instant, receiver.recv())
+                .await
+                .ok()
+                .flatten()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            receiver.recv().await
+        }
+    }
+}
+
+fn boundary_element_id(id: usize) -> String {
+    format!("hashira-suspense-{id}")
+}
+
+// Removes the hydration markers of the given number of outer components,
+// so the html only contains the `<Suspense>` the client will hydrate.
+fn strip_root_components(html: &str, depth: usize) -> &str {
+    let mut content = html;
+
+    for _ in 0..depth {
+        let (Some(start), Some(end)) = (content.find("]>-->"), content.rfind("<!--</[")) else {
+            break;
+        };
+
+        if !content.starts_with("<!--<[") || !content.ends_with("]>-->") || start >= end {
+            break;
+        }
+
+        content = &content[(start + "]>-->".len())..end];
+    }
+
+    content
+}
+
+fn render_resolved_chunk(id: usize, content: &str) -> String {
+    let boundary_id = boundary_element_id(id);
+    let template_id = format!("{boundary_id}-template");
+
+    format!(
+        r#"<template id="{template_id}">{content}</template><script>(function(){{var t=document.getElementById("{template_id}"),b=document.getElementById("{boundary_id}");if(t&&b){{b.replaceChildren(t.content);}}if(t){{t.remove();}}document.currentScript.remove();}})();</script>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_root_components;
+
+    #[test]
+    fn strip_root_components_test() {
+        let html = "<!--<[Root]>--><!--<[Provider<A>]>--><!--<[Suspense]>--><p>a</p><!--</[Suspense]>--><!--</[Provider<A>]>--><!--</[Root]>-->";
+        assert_eq!(
+            strip_root_components(html, 2),
+            "<!--<[Suspense]>--><p>a</p><!--</[Suspense]>-->"
+        );
+
+        // Release builds don't include the component names
+        let html = "<!--<[]>--><!--<?>-->b<!--</?>--><!--</[]>-->";
+        assert_eq!(strip_root_components(html, 1), "<!--<?>-->b<!--</?>-->");
+
+        assert_eq!(strip_root_components("<p>a</p>", 4), "<p>a</p>");
+    }
+}
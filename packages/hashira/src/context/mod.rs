@@ -2,4 +2,7 @@ mod server_context;
 pub use server_context::*;
 
 mod page_data_context;
-pub use page_data_context::*;
\ No newline at end of file
+pub use page_data_context::*;
+
+mod navigation_context;
+pub use navigation_context::*;
@@ -0,0 +1,631 @@
+use crate::{app::router::PageRouterWrapper, components::PageData};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use yew::{
+    function_component, hook, use_context, use_mut_ref, Children, ContextProvider, Properties,
+    UseStateHandle,
+};
+
+/// Max time a prefetched page data can be used, in milliseconds.
+#[cfg(target_arch = "wasm32")]
+const PREFETCH_MAX_AGE_MS: f64 = 30_000.0;
+
+/// Options used when navigating to other page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NavigateOptions {
+    /// Whether if replace the current history entry instead of pushing a new one.
+    pub replace: bool,
+
+    /// Whether if scroll to the top of the page after navigating.
+    pub scroll: bool,
+}
+
+impl NavigateOptions {
+    /// Constructs a default instance.
+    pub fn new() -> Self {
+        NavigateOptions {
+            replace: false,
+            scroll: true,
+        }
+    }
+
+    /// Sets whether if replace the current history entry.
+    pub fn replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    /// Sets whether if scroll to the top after navigating.
+    pub fn scroll(mut self, scroll: bool) -> Self {
+        self.scroll = scroll;
+        self
+    }
+}
+
+impl Default for NavigateOptions {
+    fn default() -> Self {
+        NavigateOptions::new()
+    }
+}
+
+/// The state we store in each history entry.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct HistoryState {
+    scroll_x: f64,
+    scroll_y: f64,
+}
+
+// The prefetched page data by url, with the time it was requested
+type PrefetchCache = HashMap<String, (f64, Option<PageData>)>;
+
+enum HistoryAction {
+    Push,
+    Replace,
+    None,
+}
+
+/// Allows to navigate between pages without reloading the page.
+///
+/// Instead of requesting the `html` of the next page we only fetch its `PageData`
+/// and swap the page component, so the state of the root component is kept alive.
+#[derive(Clone)]
+pub struct PageNavigator {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    page_data: UseStateHandle<PageData>,
+    router: PageRouterWrapper,
+
+    // Id of the last navigation, used to discard the responses of older navigations
+    navigation_id: Rc<RefCell<usize>>,
+
+    // The path and query of the page being displayed
+    current_url: Rc<RefCell<String>>,
+
+    // The scroll position to restore after the next page is rendered
+    pending_scroll: Rc<RefCell<Option<HistoryState>>>,
+
+    // Page data fetched before navigating, with the time it was requested
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    prefetched: Rc<RefCell<PrefetchCache>>,
+}
+
+impl PartialEq for PageNavigator {
+    fn eq(&self, other: &Self) -> bool {
+        self.router == other.router && Rc::ptr_eq(&self.navigation_id, &other.navigation_id)
+    }
+}
+
+impl PageNavigator {
+    /// Navigates to the given url and adds a new entry to the history.
+    pub fn push(&self, url: &str) {
+        self.navigate(url, NavigateOptions::new());
+    }
+
+    /// Navigates to the given url and replaces the current entry of the history.
+    pub fn replace(&self, url: &str) {
+        self.navigate(url, NavigateOptions::new().replace(true));
+    }
+
+    /// Navigates to the given url using the specified options.
+    ///
+    /// If the url is not a page of this app the browser will load the url.
+    pub fn navigate(&self, url: &str, options: NavigateOptions) {
+        let action = if options.replace {
+            HistoryAction::Replace
+        } else {
+            HistoryAction::Push
+        };
+
+        let scroll = options.scroll.then(HistoryState::default);
+        self.go(url, action, scroll);
+    }
+
+    /// Goes to the previous page in the history.
+    pub fn back(&self) {
+        let history = web_sys::window().unwrap().history().unwrap();
+        history.back().expect("failed to go back");
+    }
+
+    /// Goes to the next page in the history.
+    pub fn forward(&self) {
+        let history = web_sys::window().unwrap().history().unwrap();
+        history.forward().expect("failed to go forward");
+    }
+
+    /// Fetch again the data of the current page and renders it,
+    /// this runs again the page loader on the server.
+    pub fn refresh(&self) {
+        let href = web_sys::window().unwrap().location().href().unwrap();
+
+        // Ignore the cached page data
+        #[cfg(target_arch = "wasm32")]
+        crate::utils::page_cache::remove(&href);
+
+        self.go(&href, HistoryAction::None, None);
+    }
+
+    /// Returns the url if is a page that can be rendered by the client router.
+    fn resolve_page_url(&self, url: &str) -> Option<web_sys::Url> {
+        let location = web_sys::window().unwrap().location();
+        let current_href = location.href().ok()?;
+        let target = web_sys::Url::new_with_base(url, &current_href).ok()?;
+
+        // Navigation to other origins should reload
+        if target.origin() != location.origin().ok()? {
+            return None;
+        }
+
+        // The server ignore the trailing slash, so we do the same
+        let pathname = target.pathname();
+        let path = match pathname.as_str() {
+            "/" => "/",
+            p => p.trim_end_matches('/'),
+        };
+
+        self.router.find_match(path)?;
+        Some(target)
+    }
+
+    /// Fetch the data of the given page ahead of time,
+    /// so a later navigation to it don't need to wait for the server.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused_variables)]
+    pub fn prefetch(&self, url: &str) {
+        unreachable!("client only function")
+    }
+
+    /// Fetch the data of the given page ahead of time,
+    /// so a later navigation to it don't need to wait for the server.
+    #[cfg(target_arch = "wasm32")]
+    pub fn prefetch(&self, url: &str) {
+        use crate::utils::page_cache::{self, CachedPageData};
+
+        let Some(target) = self.resolve_page_url(url) else {
+            return;
+        };
+
+        let href = target.href();
+        let now = js_sys::Date::now();
+
+        // The page data still fresh in the cache
+        if let Some(CachedPageData::Fresh(_)) = page_cache::get(&href) {
+            return;
+        }
+
+        {
+            let mut prefetched = self.prefetched.borrow_mut();
+
+            // Already fetched or being fetched
+            if let Some((time, _)) = prefetched.get(&href) {
+                if now - time < PREFETCH_MAX_AGE_MS {
+                    return;
+                }
+            }
+
+            prefetched.insert(href.clone(), (now, None));
+        }
+
+        let prefetched = self.prefetched.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            match fetch_and_cache_page_data(&href).await {
+                Ok(Some(page_data)) => {
+                    prefetched.borrow_mut().insert(href, (now, Some(page_data)));
+                }
+                Ok(None) => {
+                    prefetched.borrow_mut().remove(&href);
+                }
+                Err(err) => {
+                    log::debug!("failed to prefetch `{href}`: {err}");
+                    prefetched.borrow_mut().remove(&href);
+                }
+            }
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(unused_variables)]
+    fn go(&self, url: &str, action: HistoryAction, scroll: Option<HistoryState>) {
+        unreachable!("client only function")
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn go(&self, url: &str, action: HistoryAction, scroll: Option<HistoryState>) {
+        use crate::utils::page_cache::{self, CachedPageData};
+
+        let window = web_sys::window().unwrap();
+        let location = window.location();
+
+        let Some(target) = self.resolve_page_url(url) else {
+            // Not a page we can render, let the browser handle it
+            match action {
+                HistoryAction::Replace => location.replace(url),
+                _ => location.assign(url),
+            }
+            .expect("failed to navigate");
+            return;
+        };
+
+        let id = {
+            let mut navigation_id = self.navigation_id.borrow_mut();
+            *navigation_id += 1;
+            *navigation_id
+        };
+
+        let href = target.href();
+        let mut revalidate = false;
+        let cached = self
+            .take_prefetched(&href)
+            .or_else(|| match page_cache::get(&href) {
+                Some(CachedPageData::Fresh(page_data)) => Some(page_data),
+                Some(CachedPageData::Stale(page_data)) => {
+                    revalidate = true;
+                    Some(page_data)
+                }
+                None => None,
+            });
+
+        let this = self.clone();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = match cached {
+                Some(page_data) => Ok(Some(page_data)),
+                None => fetch_and_cache_page_data(&href).await,
+            };
+
+            // Other navigation started while we were fetching
+            if *this.navigation_id.borrow() != id {
+                return;
+            }
+
+            let page_data = match result {
+                Ok(Some(page_data)) => page_data,
+                Ok(None) => {
+                    location.assign(&href).expect("failed to navigate");
+                    return;
+                }
+                Err(err) => {
+                    log::error!("failed to fetch page data for `{href}`: {err}");
+                    location.assign(&href).expect("failed to navigate");
+                    return;
+                }
+            };
+
+            let path_and_query = page_data
+                .uri
+                .path_and_query()
+                .map(|x| x.as_str().to_owned())
+                .unwrap_or_else(|| String::from("/"));
+
+            // If the server redirected we use the url of the page we received
+            let url = if page_data.uri.path() == target.pathname() {
+                href.clone()
+            } else {
+                path_and_query.clone()
+            };
+
+            let history = window.history().unwrap();
+            let state = history_state_to_js(scroll.unwrap_or_default());
+
+            match action {
+                HistoryAction::Push => {
+                    save_scroll_position();
+                    history
+                        .push_state_with_url(&state, "", Some(&url))
+                        .expect("failed to push history state");
+                }
+                HistoryAction::Replace => {
+                    history
+                        .replace_state_with_url(&state, "", Some(&url))
+                        .expect("failed to replace history state");
+                }
+                HistoryAction::None => {}
+            }
+
+            *this.pending_scroll.borrow_mut() = scroll;
+            *this.current_url.borrow_mut() = path_and_query;
+            this.page_data.set(page_data);
+
+            // We used an stale page data, so we render again with the new one
+            if revalidate {
+                match fetch_and_cache_page_data(&href).await {
+                    Ok(Some(page_data)) if *this.navigation_id.borrow() == id => {
+                        this.page_data.set(page_data);
+                    }
+                    Ok(_) => {}
+                    Err(err) => log::debug!("failed to revalidate `{href}`: {err}"),
+                }
+            }
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn take_prefetched(&self, href: &str) -> Option<PageData> {
+        let (time, page_data) = self.prefetched.borrow_mut().remove(href)?;
+
+        if js_sys::Date::now() - time > PREFETCH_MAX_AGE_MS {
+            return None;
+        }
+
+        page_data
+    }
+
+    /// Renders the page of the current location, this is called when the history changes.
+    fn on_history_change(&self, state: wasm_bindgen::JsValue) {
+        let location = web_sys::window().unwrap().location();
+        let pathname = location.pathname().unwrap_or_default();
+        let search = location.search().unwrap_or_default();
+
+        // Only the hash changed
+        if *self.current_url.borrow() == format!("{pathname}{search}") {
+            return;
+        }
+
+        let href = location.href().unwrap();
+        let scroll = history_state_from_js(state);
+        self.go(&href, HistoryAction::None, Some(scroll.unwrap_or_default()));
+    }
+
+    /// Scrolls to the position of the last navigation, if any.
+    fn restore_scroll(&self) {
+        if let Some(HistoryState { scroll_x, scroll_y }) = self.pending_scroll.borrow_mut().take() {
+            let window = web_sys::window().unwrap();
+            window.scroll_to_with_x_and_y(scroll_x, scroll_y);
+        }
+    }
+}
+
+/// Fetch the page data of the given url and stores it in the cache if the response allow it.
+#[cfg(target_arch = "wasm32")]
+async fn fetch_and_cache_page_data(href: &str) -> Result<Option<PageData>, crate::error::BoxError> {
+    use crate::utils::{page_cache, wasm::fetch_page_data};
+
+    let Some((page_data, cache_policy)) = fetch_page_data(href).await? else {
+        return Ok(None);
+    };
+
+    if let Some(cache_policy) = cache_policy {
+        page_cache::insert(href.to_owned(), page_data.clone(), cache_policy);
+    }
+
+    Ok(Some(page_data))
+}
+
+/// Stores the current scroll position in the current history entry.
+fn save_scroll_position() {
+    let window = web_sys::window().unwrap();
+    let history = window.history().unwrap();
+    let state = HistoryState {
+        scroll_x: window.scroll_x().unwrap_or_default(),
+        scroll_y: window.scroll_y().unwrap_or_default(),
+    };
+
+    if let Err(err) = history.replace_state(&history_state_to_js(state), "") {
+        log::debug!("failed to save scroll position: {err:?}");
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unused_variables)]
+fn history_state_to_js(state: HistoryState) -> wasm_bindgen::JsValue {
+    unreachable!("client only function")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn history_state_to_js(state: HistoryState) -> wasm_bindgen::JsValue {
+    serde_wasm_bindgen::to_value(&state).unwrap_or(wasm_bindgen::JsValue::NULL)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unused_variables)]
+fn history_state_from_js(value: wasm_bindgen::JsValue) -> Option<HistoryState> {
+    unreachable!("client only function")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn history_state_from_js(value: wasm_bindgen::JsValue) -> Option<HistoryState> {
+    // Other code could also be using the history state
+    serde_wasm_bindgen::from_value(value).ok()
+}
+
+/// Creates the navigator used to swap the page data of the current page.
+#[hook]
+pub(crate) fn use_create_navigator(
+    page_data: UseStateHandle<PageData>,
+    router: PageRouterWrapper,
+) -> PageNavigator {
+    let navigation_id = use_mut_ref(|| 0_usize);
+    let pending_scroll = use_mut_ref(|| None);
+    let prefetched = use_mut_ref(HashMap::new);
+    let current_url = use_mut_ref(|| {
+        page_data
+            .uri
+            .path_and_query()
+            .map(|x| x.as_str().to_owned())
+            .unwrap_or_else(|| String::from("/"))
+    });
+
+    PageNavigator {
+        page_data,
+        router,
+        navigation_id,
+        current_url,
+        pending_scroll,
+        prefetched,
+    }
+}
+
+/// Intercepts the clicks to `<a>` elements and the history changes to navigate
+/// using the given navigator, and restores the scroll position after a page is rendered.
+#[hook]
+pub(crate) fn use_intercept_navigation(navigator: &PageNavigator, page_data: &PageData) {
+    {
+        let navigator = navigator.clone();
+        yew::use_effect_with_deps(
+            move |_| {
+                let listeners = listeners::intercept_navigation(navigator);
+                move || drop(listeners)
+            },
+            (),
+        );
+    }
+
+    let navigator = navigator.clone();
+    yew::use_effect_with_deps(
+        move |_| {
+            navigator.restore_scroll();
+        },
+        page_data.clone(),
+    );
+}
+
+mod listeners {
+    use super::{history_state_from_js, save_scroll_position, PageNavigator};
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{Event, EventTarget, HtmlAnchorElement, MouseEvent, PopStateEvent};
+
+    /// Removes the event listener when dropped.
+    pub struct EventListener {
+        target: EventTarget,
+        event_type: &'static str,
+        callback: Closure<dyn FnMut(Event)>,
+    }
+
+    impl EventListener {
+        fn new<F>(target: EventTarget, event_type: &'static str, f: F) -> Self
+        where
+            F: FnMut(Event) + 'static,
+        {
+            let callback = Closure::wrap(Box::new(f) as Box<dyn FnMut(Event)>);
+            target
+                .add_event_listener_with_callback(event_type, callback.as_ref().unchecked_ref())
+                .expect("failed to add event listener");
+
+            EventListener {
+                target,
+                event_type,
+                callback,
+            }
+        }
+    }
+
+    impl Drop for EventListener {
+        fn drop(&mut self) {
+            let _ = self.target.remove_event_listener_with_callback(
+                self.event_type,
+                self.callback.as_ref().unchecked_ref(),
+            );
+        }
+    }
+
+    pub fn intercept_navigation(navigator: PageNavigator) -> Vec<EventListener> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let history = window.history().unwrap();
+
+        // We restore the scroll position ourselves
+        let _ = history.set_scroll_restoration(web_sys::ScrollRestoration::Manual);
+
+        // Restore the scroll position after a reload
+        if let Some(state) = history.state().ok().and_then(history_state_from_js) {
+            window.scroll_to_with_x_and_y(state.scroll_x, state.scroll_y);
+        }
+
+        let on_click = {
+            let navigator = navigator.clone();
+            EventListener::new(document.into(), "click", move |event| {
+                if let Ok(event) = event.dyn_into::<MouseEvent>() {
+                    on_link_click(&event, &navigator);
+                }
+            })
+        };
+
+        let on_popstate = EventListener::new(window.clone().into(), "popstate", move |event| {
+            if let Ok(event) = event.dyn_into::<PopStateEvent>() {
+                navigator.on_history_change(event.state());
+            }
+        });
+
+        let on_pagehide = EventListener::new(window.into(), "pagehide", move |_| {
+            save_scroll_position();
+        });
+
+        vec![on_click, on_popstate, on_pagehide]
+    }
+
+    /// Returns `true` if the click should be handled by the client router.
+    pub fn is_navigation_click(event: &MouseEvent) -> bool {
+        // Only handle plain left clicks
+        !(event.default_prevented()
+            || event.button() != 0
+            || event.meta_key()
+            || event.ctrl_key()
+            || event.shift_key()
+            || event.alt_key())
+    }
+
+    fn on_link_click(event: &MouseEvent, navigator: &PageNavigator) {
+        if !is_navigation_click(event) {
+            return;
+        }
+
+        let Some(anchor) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|element| element.closest("a[href]").ok().flatten())
+            .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok())
+        else {
+            return;
+        };
+
+        // Links to other windows, downloads or marked to reload are handled by the browser
+        let target = anchor.target();
+        if (!target.is_empty() && target != "_self")
+            || anchor.has_attribute("download")
+            || anchor.has_attribute("data-hashira-reload")
+        {
+            return;
+        }
+
+        let href = anchor.href();
+        let Some(url) = navigator.resolve_page_url(&href) else {
+            return;
+        };
+
+        // Only the hash changed, the browser will scroll to the element
+        let location = web_sys::window().unwrap().location();
+        if !url.hash().is_empty()
+            && Ok(url.pathname()) == location.pathname()
+            && Ok(url.search()) == location.search()
+        {
+            return;
+        }
+
+        event.prevent_default();
+        navigator.push(&href);
+    }
+}
+
+pub(crate) use listeners::is_navigation_click;
+
+#[doc(hidden)]
+#[derive(PartialEq, Properties)]
+pub struct PageNavigatorProps {
+    pub navigator: PageNavigator,
+    pub children: Children,
+}
+
+/// Provides the `PageNavigator` to the children components.
+#[doc(hidden)]
+#[function_component]
+pub fn PageNavigatorProvider(props: &PageNavigatorProps) -> yew::Html {
+    yew::html! {
+        <ContextProvider<PageNavigator> context={props.navigator.clone()}>
+            {for props.children.iter()}
+        </ContextProvider<PageNavigator>>
+    }
+}
+
+/// Returns a handle to navigate between the pages of the app.
+#[hook]
+pub fn use_navigate() -> PageNavigator {
+    use_context::<PageNavigator>().expect("`PageNavigatorProvider` should be a parent")
+}
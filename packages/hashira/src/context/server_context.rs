@@ -1,10 +1,15 @@
-use crate::app::RequestContext;
+use crate::{
+    app::RequestContext,
+    components::{DeferredValues, SuspenseStreamer},
+};
 use std::ops::Deref;
 use yew::{function_component, hook, use_context, Children, ContextProvider, Properties};
 
 #[derive(Clone)]
 struct ServerContextInner {
     ctx: RequestContext,
+    streamer: Option<SuspenseStreamer>,
+    deferred: DeferredValues,
 }
 
 impl Deref for ServerContextInner {
@@ -30,9 +35,43 @@ pub struct ServerContext {
 impl ServerContext {
     pub(crate) fn new(ctx: Option<RequestContext>) -> Self {
         ServerContext {
-            inner: ctx.map(|ctx| ServerContextInner { ctx }),
+            inner: ctx.map(|ctx| ServerContextInner {
+                ctx,
+                streamer: None,
+                deferred: Default::default(),
+            }),
         }
     }
+
+    /// Sets the streamer used to render the `StreamingSuspense` boundaries out of order.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn with_streamer(mut self, streamer: SuspenseStreamer) -> Self {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.streamer = Some(streamer);
+        }
+
+        self
+    }
+
+    /// Sets the `Deferred` values of the page props.
+    #[cfg_attr(feature = "client", allow(dead_code))]
+    pub(crate) fn with_deferred(mut self, deferred: DeferredValues) -> Self {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.deferred = deferred;
+        }
+
+        self
+    }
+
+    /// Returns the `Deferred` values of the current page, if any.
+    pub(crate) fn deferred(&self) -> Option<&DeferredValues> {
+        self.inner.as_ref().map(|inner| &inner.deferred)
+    }
+
+    /// Returns the streamer of the current render, if any.
+    pub(crate) fn streamer(&self) -> Option<&SuspenseStreamer> {
+        self.inner.as_ref().and_then(|inner| inner.streamer.as_ref())
+    }
 }
 
 impl Deref for ServerContext {
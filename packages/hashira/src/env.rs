@@ -21,6 +21,9 @@ pub(crate) const HASHIRA_LIVE_RELOAD_PORT: &str = "HASHIRA_LIVE_RELOAD_PORT";
 /// Name of the environment variable with the name of the wasm library.
 pub(crate) const HASHIRA_WASM_LIB: &str = "HASHIRA_WASM_LIB";
 
+/// Name of the environment variable with the directory where the app will be exported as static files.
+pub(crate) const HASHIRA_EXPORT_DIR: &str = "HASHIRA_EXPORT_DIR";
+
 /// Returns the name of the wasm client library.
 pub(crate) fn get_client_name() -> Option<String> {
     if let Some(name) = get_env(HASHIRA_WASM_LIB) {
@@ -53,6 +56,11 @@ pub fn get_static_dir() -> String {
     get_env(HASHIRA_STATIC_DIR).unwrap_or_else(|| "/static".into())
 }
 
+/// Returns the directory to export the application, if the app is being exported instead of served.
+pub fn get_export_dir() -> Option<std::path::PathBuf> {
+    get_env(HASHIRA_EXPORT_DIR).map(std::path::PathBuf::from)
+}
+
 /// Returns `true` if the application has live reload.
 pub fn is_live_reload() -> bool {
     if let Some(env) = get_env(HASHIRA_LIVE_RELOAD) {
@@ -1,4 +1,5 @@
 use super::BoxError;
+use crate::actions::ValidationErrors;
 use crate::web::{IntoResponse, Response, ResponseExt};
 use http::StatusCode;
 use std::fmt::{Debug, Display};
@@ -64,6 +65,12 @@ impl ServerError {
             return *error.downcast().unwrap();
         }
 
+        // Validation errors are sent as json so the client can read them
+        if error.is::<ValidationErrors>() {
+            let errors = *error.downcast::<ValidationErrors>().unwrap();
+            return ServerError::from_response(errors);
+        }
+
         let msg = error.to_string();
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
@@ -2,9 +2,15 @@
 mod use_query_params;
 pub use use_query_params::*;
 
+//
+mod use_deferred;
+pub use use_deferred::*;
+
 //
 mod common;
 pub use common::*;
 
 // Reexport
-pub use crate::context::{use_page_data, use_server_context};
+pub use crate::context::{
+    use_navigate, use_page_data, use_server_context, NavigateOptions, PageNavigator,
+};
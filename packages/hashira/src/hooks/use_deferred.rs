@@ -0,0 +1,63 @@
+use crate::{
+    components::{Deferred, DeferredError},
+    context::use_server_context,
+};
+use serde::de::DeserializeOwned;
+use yew::{
+    hook,
+    suspense::{Suspension, SuspensionResult},
+    use_memo, use_state,
+};
+
+/// Returns the value of a `Deferred` prop, suspends until the value is received.
+///
+/// On the server the component suspends until the loader resolves the value,
+/// wrap it in a `StreamingSuspense` to send the rest of the page in the meantime.
+///
+/// Returns an error if the value is not part of the page or cannot be deserialized as `T`.
+#[hook]
+pub fn use_deferred<T>(deferred: &Deferred<T>) -> SuspensionResult<Result<T, DeferredError>>
+where
+    T: DeserializeOwned + 'static,
+{
+    let server_context = use_server_context();
+    let received = use_state(|| None::<Result<serde_json::Value, DeferredError>>);
+    let value = deferred
+        .resolved_value()
+        .map(Ok)
+        .or_else(|| (*received).clone());
+
+    let suspension = {
+        let received = received.clone();
+        let id = deferred.id();
+        let deferred = deferred.clone();
+        let is_pending = value.is_none();
+
+        // The suspension resumes when the value is received, we create a new one each time is pending
+        use_memo(
+            move |_| {
+                if !is_pending {
+                    return None;
+                }
+
+                let suspension = Suspension::from_future(async move {
+                    let value = deferred.wait(server_context).await;
+                    received.set(Some(value));
+                });
+
+                Some(suspension)
+            },
+            (id, is_pending),
+        )
+    };
+
+    match value {
+        Some(Ok(json)) => Ok(serde_json::from_value::<T>(json)
+            .map_err(|err| DeferredError::Deserialize(err.to_string()))),
+        Some(Err(err)) => Ok(Err(err)),
+        None => match suspension.as_ref() {
+            Some(suspension) => Err(suspension.clone()),
+            None => unreachable!("pending deferred value without a suspension"),
+        },
+    }
+}
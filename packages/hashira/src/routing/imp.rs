@@ -31,22 +31,6 @@ impl<T> BaseRouter<T> {
                 other => MatchError::Other(other.into()),
             })
     }
-
-    pub fn find_mut(
-        &mut self,
-        path: impl AsRef<str>,
-    ) -> Result<RouteMatch<&mut T>, MatchError> {
-        self.inner
-            .at_mut(path.as_ref())
-            .map(|Match { value, params }| RouteMatch {
-                value,
-                params: Params::from_iter(params.iter()),
-            })
-            .map_err(|e| match e {
-                matchit::MatchError::NotFound => MatchError::NotFound,
-                other => MatchError::Other(other.into()),
-            })
-    }
 }
 
 impl<T> Default for BaseRouter<T> {
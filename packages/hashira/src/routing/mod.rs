@@ -3,9 +3,13 @@ mod imp;
 //
 mod path_router;
 mod params;
+mod route_pattern;
+mod page_url;
 
 pub use path_router::*;
 pub use params::*;
+pub use route_pattern::*;
+pub use page_url::*;
 
 mod server_router;
 pub use server_router::*;
@@ -1,4 +1,4 @@
-use crate::components::{id::PageId, AnyComponent};
+use crate::components::{id::PageId, AnyComponent, PageLayout};
 
 // Represents a client-side page route, containing a component and a path pattern.
 #[derive(Clone)]
@@ -6,15 +6,26 @@ pub struct ClientPageRoute {
     pub(crate) page_id: PageId,
     pub(crate) component: AnyComponent<serde_json::Value>, // The component for this page route.
     pub(crate) path: String,                               // The route of this component
+    pub(crate) layouts: Vec<PageLayout>, // The layouts from outermost to innermost
 }
 
 impl ClientPageRoute {
     /// Returns this route with a new path.
     pub fn with_path(self, path: impl Into<String>) -> Self {
         ClientPageRoute {
-            page_id: self.page_id,
-            component: self.component,
             path: path.into(),
+            ..self
+        }
+    }
+
+    /// Returns this route wrapped with the given layouts, the layouts are placed outside the current ones.
+    pub fn with_layouts(self, layouts: &[PageLayout]) -> Self {
+        let mut new_layouts = layouts.to_vec();
+        new_layouts.extend(self.layouts);
+
+        ClientPageRoute {
+            layouts: new_layouts,
+            ..self
         }
     }
 
@@ -25,11 +36,22 @@ impl ClientPageRoute {
 
     // Renders the component for this page route with the given props.
     pub fn render(&self, props: serde_json::Value) -> yew::Html {
-        self.component.render_with_props(props)
+        let page = self.component.render_with_props(props);
+
+        // Wrap the page from the innermost to the outermost layout
+        self.layouts
+            .iter()
+            .rev()
+            .fold(page, |children, layout| layout.render(children))
     }
 
     // Returns a reference to the path pattern for this page route.
     pub fn path(&self) -> &str {
         self.path.as_str()
     }
+
+    /// Returns the layouts of this page route, from outermost to innermost.
+    pub fn layouts(&self) -> &[PageLayout] {
+        &self.layouts
+    }
 }
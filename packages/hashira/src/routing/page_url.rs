@@ -0,0 +1,192 @@
+use crate::components::{id::PageId, PageComponent};
+use once_cell::sync::Lazy;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display, sync::RwLock};
+use yew::{html::IntoPropValue, AttrValue};
+
+// Characters that are escaped in a path segment
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// The base path of the pages registered in a nested app
+static BASE_PATHS: Lazy<RwLock<HashMap<PageId, String>>> = Lazy::new(Default::default);
+
+/// Registers the base path of a page added in a nested app, which is prepended to its urls.
+pub(crate) fn register_base_path(page_id: PageId, base_path: &str) {
+    let mut base_paths = BASE_PATHS.write().unwrap_or_else(|err| err.into_inner());
+    base_paths.insert(page_id, base_path.trim_end_matches('/').to_owned());
+}
+
+/// The url to a page.
+///
+/// The `#[page_component]` macro generates an `url` function for each page with a route,
+/// which takes the params of the route, so changing a route fails to compile
+/// instead of breaking the links.
+///
+/// The url of a page added with `App::nest` includes the base path of the nested app,
+/// if the same page is nested under more than one path the last one is used.
+///
+/// # Example
+/// ```rust,ignore
+/// #[page_component("/todos/{id:u64}/edit")]
+/// fn EditTodoPage() -> yew::Html {
+///     // ...
+/// }
+///
+/// html! {
+///     <Link to={EditTodoPage::url(todo.id)}>{"Edit"}</Link>
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PageUrl {
+    path: String,
+    query: Option<String>,
+}
+
+impl PageUrl {
+    /// Constructs an url to the given path.
+    pub fn new(path: impl Into<String>) -> Self {
+        PageUrl {
+            path: path.into(),
+            query: None,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn builder() -> Self {
+        PageUrl::new(String::new())
+    }
+
+    #[doc(hidden)]
+    pub fn for_page<COMP: PageComponent>() -> Self {
+        let base_paths = BASE_PATHS.read().unwrap_or_else(|err| err.into_inner());
+        let base_path = base_paths.get(&PageId::of::<COMP>()).cloned();
+        PageUrl::new(base_path.unwrap_or_default())
+    }
+
+    #[doc(hidden)]
+    pub fn push_segment(mut self, segment: &str) -> Self {
+        self.path.push('/');
+        self.path.push_str(segment);
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn push_param(mut self, value: &dyn Display) -> Self {
+        let value = value.to_string();
+        self.path.push('/');
+        self.path.extend(utf8_percent_encode(&value, SEGMENT));
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn push_catch_all(mut self, value: &dyn Display) -> Self {
+        let value = value.to_string();
+        for part in value.trim_matches('/').split('/') {
+            self.path.push('/');
+            self.path.extend(utf8_percent_encode(part, SEGMENT));
+        }
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn build(mut self) -> Self {
+        if self.path.is_empty() {
+            self.path.push('/');
+        }
+        self
+    }
+
+    /// Sets the query string of the url, an empty query removes it.
+    ///
+    /// # Panic
+    /// If the value cannot be serialized as a query string.
+    pub fn query<Q: Serialize>(mut self, query: &Q) -> Self {
+        let query = serde_qs::to_string(query).expect("failed to serialize query string");
+        self.query = Some(query).filter(|x| !x.is_empty());
+        self
+    }
+
+    /// Returns the path of the url.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the query string of the url, if any.
+    pub fn query_string(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+}
+
+impl Display for PageUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.query {
+            Some(query) => write!(f, "{}?{}", self.path, query),
+            None => f.write_str(&self.path),
+        }
+    }
+}
+
+impl From<PageUrl> for String {
+    fn from(value: PageUrl) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<PageUrl> for AttrValue {
+    fn from(value: PageUrl) -> Self {
+        AttrValue::from(value.to_string())
+    }
+}
+
+impl IntoPropValue<AttrValue> for PageUrl {
+    fn into_prop_value(self) -> AttrValue {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageUrl;
+    use serde::Serialize;
+
+    #[test]
+    fn page_url_test() {
+        let url = PageUrl::builder()
+            .push_segment("todos")
+            .push_param(&"a b/c")
+            .push_catch_all(&"docs/intro")
+            .build();
+
+        assert_eq!(url.to_string(), "/todos/a%20b%2Fc/docs/intro");
+        assert_eq!(PageUrl::builder().build().path(), "/");
+    }
+
+    #[test]
+    fn page_url_query_test() {
+        #[derive(Serialize)]
+        struct Search {
+            q: String,
+            page: u32,
+        }
+
+        let url = PageUrl::new("/search").query(&Search {
+            q: "rust".to_owned(),
+            page: 2,
+        });
+
+        assert_eq!(url.query_string(), Some("q=rust&page=2"));
+        assert_eq!(url.to_string(), "/search?q=rust&page=2");
+    }
+}
@@ -1,4 +1,5 @@
-use super::{imp, Params};
+use super::{imp, Params, RoutePattern};
+use std::collections::HashMap;
 use thiserror::Error;
 
 /// The result of a match.
@@ -11,8 +12,18 @@ pub struct RouteMatch<T> {
 }
 
 /// A router.
+///
+/// The routes can declare constraints on its params, see [`RoutePattern`].
 pub struct PathRouter<T> {
-    imp: imp::BaseRouter<T>,
+    // Maps the routes without constraints to the indices of the entries that share it
+    imp: imp::BaseRouter<usize>,
+    buckets: Vec<Vec<usize>>,
+    bucket_ids: HashMap<String, usize>,
+    entries: Vec<(RoutePattern, T)>,
+    // Indices of the entries from the most to the less specific
+    sorted: Vec<usize>,
+    // Indices of the entries with a route `matchit` rejected as a conflict
+    unindexed: Vec<usize>,
 }
 
 impl<T> PathRouter<T> {
@@ -20,24 +31,104 @@ impl<T> PathRouter<T> {
     pub fn new() -> Self {
         PathRouter {
             imp: imp::BaseRouter::new(),
+            buckets: vec![],
+            bucket_ids: HashMap::new(),
+            entries: vec![],
+            sorted: vec![],
+            unindexed: vec![],
         }
     }
 
     /// Insert the given value at the given route.
     pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError> {
         let route = route.into();
-        assert_valid_route(&route).map_err(|err| InsertError(err.into()))?;
-        self.imp.insert(route, value)
+        let pattern = parse_route(&route).map_err(|err| InsertError(err.into()))?;
+
+        if let Some((other, _)) = self.entries.iter().find(|(p, _)| p.is_equivalent(&pattern)) {
+            return Err(InsertError(
+                format!("route `{pattern}` conflicts with `{other}`").into(),
+            ));
+        }
+
+        let index = self.entries.len();
+
+        for key in pattern.matchit_routes() {
+            if let Some(bucket_id) = self.bucket_ids.get(&key) {
+                self.buckets[*bucket_id].push(index);
+                continue;
+            }
+
+            let bucket_id = self.buckets.len();
+            if self.imp.insert(key.clone(), bucket_id).is_ok() {
+                self.buckets.push(vec![index]);
+                self.bucket_ids.insert(key, bucket_id);
+            } else if !self.unindexed.contains(&index) {
+                self.unindexed.push(index);
+            }
+        }
+
+        let pos = self
+            .sorted
+            .partition_point(|i| self.entries[*i].0.cmp_specificity(&pattern).is_le());
+
+        self.sorted.insert(pos, index);
+        self.entries.push((pattern, value));
+        Ok(())
     }
 
     /// Returns the match for the given path or error if not found.
     pub fn find(&self, path: impl AsRef<str>) -> Result<RouteMatch<&T>, MatchError> {
-        self.imp.find(path)
+        let (index, params) = self.find_index(path.as_ref())?;
+        Ok(RouteMatch {
+            value: &self.entries[index].1,
+            params,
+        })
     }
 
     /// Returns a mutable reference to the match for the given path or error if not found.
     pub fn find_mut(&mut self, path: impl AsRef<str>) -> Result<RouteMatch<&mut T>, MatchError> {
-        self.imp.find_mut(path)
+        let (index, params) = self.find_index(path.as_ref())?;
+        Ok(RouteMatch {
+            value: &mut self.entries[index].1,
+            params,
+        })
+    }
+
+    fn find_index(&self, path: &str) -> Result<(usize, Params), MatchError> {
+        let bucket = match self.imp.find(path) {
+            Ok(m) => self.buckets[*m.value].as_slice(),
+            Err(MatchError::NotFound) => &[],
+            Err(err) => return Err(err),
+        };
+
+        let found = bucket.iter().find_map(|index| {
+            let params = self.entries[*index].0.matches(path)?;
+            Some((*index, params))
+        });
+
+        if let Some((index, params)) = found {
+            // A route rejected by `matchit` may be more specific than the match
+            let pattern = &self.entries[index].0;
+            let better = self.unindexed.iter().find_map(|i| {
+                let (other, _) = &self.entries[*i];
+                if !other.cmp_specificity(pattern).is_lt() {
+                    return None;
+                }
+
+                Some((*i, other.matches(path)?))
+            });
+
+            return Ok(better.unwrap_or((index, params)));
+        }
+
+        // The constraints of the best match failed, try the other routes
+        for index in self.sorted.iter().filter(|i| !bucket.contains(i)) {
+            if let Some(params) = self.entries[*index].0.matches(path) {
+                return Ok((*index, params));
+            }
+        }
+
+        Err(MatchError::NotFound)
     }
 }
 
@@ -65,6 +156,10 @@ pub enum MatchError {
 }
 
 pub(crate) fn assert_valid_route(path: &str) -> Result<(), String> {
+    parse_route(path).map(|_| ())
+}
+
+fn parse_route(path: &str) -> Result<RoutePattern, String> {
     if path.is_empty() {
         return Err(String::from("route path cannot be empty"));
     }
@@ -84,7 +179,7 @@ pub(crate) fn assert_valid_route(path: &str) -> Result<(), String> {
         return Err(format!("route path cannot end with `/` but was: {}", path));
     }
 
-    Ok(())
+    RoutePattern::parse(path)
 }
 
 #[cfg(test)]
@@ -171,6 +266,59 @@ mod tests {
         assert_eq!(*match_result.value, "test-value");
     }
 
+    #[test]
+    fn test_invalid_constraint_route() {
+        let result = assert_valid_route("/users/{id:uuid}");
+        assert_eq!(
+            result.err(),
+            Some(String::from(
+                "unknown type `uuid` for param `id`: /users/{id:uuid}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_insert_conflict() {
+        let mut router = PathRouter::new();
+        router.insert("/users/:id", 1).unwrap();
+        assert!(router.insert("/users/{user_id}", 2).is_err());
+        assert!(router.insert("/users/{id:u64}", 3).is_ok());
+    }
+
+    #[test]
+    fn test_find_constrained_match() {
+        let mut router = PathRouter::new();
+        router.insert("/users/{id:u64}", "by-id").unwrap();
+        router.insert("/users/{name}", "by-name").unwrap();
+        router.insert("/files/{*path}", "files").unwrap();
+        router.insert("/files/{id:u32}", "file-by-id").unwrap();
+        router.insert("/blog/{page?}", "blog").unwrap();
+        router.insert("/posts/{id:u64}", "post").unwrap();
+
+        let result = router.find("/users/12").unwrap();
+        assert_eq!(*result.value, "by-id");
+        assert_eq!(result.params.get("id"), Some("12"));
+
+        let result = router.find("/users/john").unwrap();
+        assert_eq!(*result.value, "by-name");
+        assert_eq!(result.params.get("name"), Some("john"));
+
+        assert_eq!(*router.find("/files/7").unwrap().value, "file-by-id");
+        assert_eq!(*router.find("/files/a.txt").unwrap().value, "files");
+        assert_eq!(*router.find("/files/a/b.txt").unwrap().value, "files");
+
+        assert_eq!(*router.find("/blog").unwrap().value, "blog");
+        assert_eq!(
+            router.find("/blog/2").unwrap().params.get("page"),
+            Some("2")
+        );
+
+        assert!(matches!(
+            router.find("/posts/latest").err().unwrap(),
+            MatchError::NotFound
+        ));
+    }
+
     #[test]
     fn test_find_match_not_found() {
         let router = PathRouter::<&str>::new();
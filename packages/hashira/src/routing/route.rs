@@ -4,7 +4,7 @@ use std::future::Future;
 
 use super::RouteMethod;
 use crate::{
-    app::{Handler, PageHandler},
+    app::{Handler, Middleware, PageHandler},
     web::{FromRequest, IntoResponse},
 };
 
@@ -174,6 +174,23 @@ impl Route {
         &self.handler
     }
 
+    /// Returns a mutable reference to the handler function for this `Route`.
+    pub(crate) fn handler_mut(&mut self) -> &mut PageHandler {
+        &mut self.handler
+    }
+
+    /// Adds a middleware that wraps the handler of this route.
+    ///
+    /// The middlewares run in the order they were added, after the middlewares of the app
+    /// and the nested scopes.
+    pub fn layer<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware,
+    {
+        self.handler.push_layer(std::sync::Arc::new(middleware));
+        self
+    }
+
     /// Metadata of the route.
     pub fn extensions(&self) -> &Extensions {
         &self.extensions
@@ -47,6 +47,25 @@ impl RouteMethod {
     pub fn all() -> RouteMethod {
         RouteMethod(0b1111_1111)
     }
+
+    /// Returns an iterator over the HTTP methods this `RouteMethod` matches.
+    pub fn methods(&self) -> impl Iterator<Item = Method> + '_ {
+        const METHODS: [(RouteMethod, Method); 8] = [
+            (RouteMethod::GET, Method::GET),
+            (RouteMethod::POST, Method::POST),
+            (RouteMethod::PUT, Method::PUT),
+            (RouteMethod::PATCH, Method::PATCH),
+            (RouteMethod::DELETE, Method::DELETE),
+            (RouteMethod::HEAD, Method::HEAD),
+            (RouteMethod::OPTIONS, Method::OPTIONS),
+            (RouteMethod::TRACE, Method::TRACE),
+        ];
+
+        METHODS
+            .into_iter()
+            .filter(|(m, _)| self.matches(m))
+            .map(|(_, method)| method)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -0,0 +1,352 @@
+use super::Params;
+use hashira_route::split_segments;
+use percent_encoding::percent_decode_str;
+use std::{borrow::Cow, fmt::Display};
+
+pub use hashira_route::{ParamType, RouteSegment};
+
+// Lower values are tried first when more than one route matches a path.
+fn specificity(segment: &RouteSegment) -> u8 {
+    match segment {
+        RouteSegment::Static(_) => 0,
+        RouteSegment::Param {
+            ty: Some(_),
+            optional: false,
+            ..
+        } => 1,
+        RouteSegment::Param {
+            optional: false, ..
+        } => 2,
+        RouteSegment::Param { .. } => 3,
+        RouteSegment::CatchAll { .. } => 4,
+    }
+}
+
+// Returns `true` if both segments match the same values, regardless the param names.
+fn is_equivalent_segment(a: &RouteSegment, b: &RouteSegment) -> bool {
+    match (a, b) {
+        (RouteSegment::Static(a), RouteSegment::Static(b)) => a == b,
+        (
+            RouteSegment::Param {
+                ty: ty_a,
+                optional: optional_a,
+                ..
+            },
+            RouteSegment::Param {
+                ty: ty_b,
+                optional: optional_b,
+                ..
+            },
+        ) => ty_a == ty_b && optional_a == optional_b,
+        (RouteSegment::CatchAll { .. }, RouteSegment::CatchAll { .. }) => true,
+        _ => false,
+    }
+}
+
+/// A parsed route path.
+///
+/// Besides the `:name` and `*name` params, the segments of a route can declare:
+/// - `{name}`: a param.
+/// - `{name:type}`: a param that must be parsed to the given type, for example `{id:u64}`.
+/// - `{name?}`: a param that can be omitted, must be the last segment.
+/// - `{*name}`: a param that captures the rest of the path, must be the last segment.
+///
+/// A path that don't satisfy the constraints of a route don't match it,
+/// and is tried against the other routes.
+///
+/// # Example
+/// ```rust,ignore
+/// let pattern = RoutePattern::parse("/users/{id:u64}").unwrap();
+/// assert!(pattern.matches("/users/12").is_some());
+/// assert!(pattern.matches("/users/me").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePattern {
+    path: String,
+    segments: Vec<RouteSegment>,
+}
+
+impl RoutePattern {
+    /// Parses the given route path.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let segments = hashira_route::parse_segments(path)?;
+
+        Ok(RoutePattern {
+            path: path.to_owned(),
+            segments,
+        })
+    }
+
+    /// Returns the route path.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the segments of the route.
+    pub fn segments(&self) -> &[RouteSegment] {
+        &self.segments
+    }
+
+    /// Returns `true` if the route have any param.
+    pub fn is_dynamic(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| !matches!(s, RouteSegment::Static(_)))
+    }
+
+    /// Returns the params if the given path matches this route and satisfy its constraints.
+    pub fn matches(&self, path: &str) -> Option<Params> {
+        let rest = path.strip_prefix('/')?;
+        let mut parts = split_segments(rest);
+        let mut params = vec![];
+
+        for segment in &self.segments {
+            match segment {
+                RouteSegment::Static(s) => {
+                    if parts.next() != Some(s.as_str()) {
+                        return None;
+                    }
+                }
+                RouteSegment::Param { name, ty, optional } => match parts.next() {
+                    Some(value) if !value.is_empty() => {
+                        if let Some(ty) = ty {
+                            if !ty.is_valid(&decode_param(value)?) {
+                                return None;
+                            }
+                        }
+
+                        params.push((name.as_str(), value.to_owned()));
+                    }
+                    None if *optional => {}
+                    _ => return None,
+                },
+                RouteSegment::CatchAll { name } => {
+                    let value = parts.by_ref().collect::<Vec<_>>().join("/");
+                    if value.is_empty() {
+                        return None;
+                    }
+
+                    params.push((name.as_str(), value));
+                }
+            }
+        }
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Params::from_iter(
+            params.into_iter().map(|(k, v)| (k.to_owned(), v)),
+        ))
+    }
+
+    /// Replaces the params of the route with the given values.
+    pub fn format(&self, params: &Params) -> Result<String, String> {
+        let mut path = String::new();
+
+        for segment in &self.segments {
+            let (name, ty, is_catch_all) = match segment {
+                RouteSegment::Static(s) => {
+                    path.push('/');
+                    path.push_str(s);
+                    continue;
+                }
+                RouteSegment::Param { name, ty, optional } => match params.get(name) {
+                    Some(_) => (name, *ty, false),
+                    None if *optional => break,
+                    None => {
+                        return Err(format!("missing param `{name}` for route `{}`", self.path))
+                    }
+                },
+                RouteSegment::CatchAll { name } => (name, None, true),
+            };
+
+            let value = params
+                .get(name)
+                .ok_or_else(|| format!("missing param `{name}` for route `{}`", self.path))?;
+
+            let is_valid = !value.is_empty()
+                && (is_catch_all || !value.contains('/'))
+                && ty.map(|ty| ty.is_valid(value)).unwrap_or(true);
+
+            if !is_valid {
+                return Err(format!(
+                    "invalid value `{value}` for param `{name}` of route `{}`",
+                    self.path
+                ));
+            }
+
+            path.push('/');
+            path.push_str(value.trim_matches('/'));
+        }
+
+        if path.is_empty() {
+            path.push('/');
+        }
+
+        Ok(path)
+    }
+
+    /// Returns `true` if both routes match the same paths.
+    pub(crate) fn is_equivalent(&self, other: &RoutePattern) -> bool {
+        self.segments.len() == other.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(other.segments.iter())
+                .all(|(a, b)| is_equivalent_segment(a, b))
+    }
+
+    /// Compares the specificity of the routes, the more specific routes are tried first.
+    pub(crate) fn cmp_specificity(&self, other: &RoutePattern) -> std::cmp::Ordering {
+        let a = self.segments.iter().map(specificity);
+        let b = other.segments.iter().map(specificity);
+        a.cmp(b)
+    }
+
+    /// Returns the routes registered in `matchit` for this pattern,
+    /// the params are renamed by position and the constraints are checked after a match.
+    pub(crate) fn matchit_routes(&self) -> Vec<String> {
+        let mut routes = vec![];
+        let mut route = String::new();
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            match segment {
+                RouteSegment::Static(s) => {
+                    route.push('/');
+                    route.push_str(s);
+                }
+                RouteSegment::Param { optional, .. } => {
+                    if *optional {
+                        routes.push(if route.is_empty() {
+                            String::from("/")
+                        } else {
+                            route.clone()
+                        });
+                    }
+
+                    route.push_str(&format!("/:p{index}"));
+                }
+                RouteSegment::CatchAll { .. } => {
+                    route.push_str(&format!("/*p{index}"));
+                }
+            }
+        }
+
+        routes.push(if route.is_empty() {
+            String::from("/")
+        } else {
+            route
+        });
+        routes
+    }
+}
+
+impl Display for RoutePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// Decodes the percent-encoded value of a param, returns `None` if is not valid utf-8.
+pub(crate) fn decode_param(value: &str) -> Option<Cow<'_, str>> {
+    percent_decode_str(value).decode_utf8().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParamType, RoutePattern, RouteSegment};
+    use crate::routing::Params;
+
+    #[test]
+    fn parse_route_pattern_test() {
+        let pattern = RoutePattern::parse("/users/{id:u64}/:tab/{*rest}").unwrap();
+        assert_eq!(
+            pattern.segments(),
+            &[
+                RouteSegment::Static("users".to_owned()),
+                RouteSegment::Param {
+                    name: "id".to_owned(),
+                    ty: Some(ParamType::U64),
+                    optional: false
+                },
+                RouteSegment::Param {
+                    name: "tab".to_owned(),
+                    ty: None,
+                    optional: false
+                },
+                RouteSegment::CatchAll {
+                    name: "rest".to_owned()
+                },
+            ]
+        );
+
+        assert!(RoutePattern::parse("/users/{id:uuid}").is_err());
+        assert!(RoutePattern::parse("/blog/{page?}/posts").is_err());
+        assert!(RoutePattern::parse("/docs/{*rest}/edit").is_err());
+        assert!(RoutePattern::parse("/users/{id}/{id}").is_err());
+        assert!(RoutePattern::parse("/users/user_{id}").is_err());
+        assert!(RoutePattern::parse("/users//posts").is_err());
+    }
+
+    #[test]
+    fn param_names_test() {
+        // Any name accepted by `matchit` is still valid
+        let pattern = RoutePattern::parse("/posts/:post-id/{slug.v2?}").unwrap();
+        let params = pattern.matches("/posts/1/hello").unwrap();
+        assert_eq!(params.get("post-id"), Some("1"));
+        assert_eq!(params.get("slug.v2"), Some("hello"));
+
+        assert_eq!(
+            RoutePattern::parse("/users/{}").unwrap_err(),
+            "param name cannot be empty: /users/{}"
+        );
+        assert_eq!(
+            RoutePattern::parse("/users/{id?:u64}").unwrap_err(),
+            "invalid param name `id?`: /users/{id?:u64}"
+        );
+    }
+
+    #[test]
+    fn route_pattern_matches_test() {
+        let pattern = RoutePattern::parse("/users/{id:u64}").unwrap();
+        assert_eq!(pattern.matches("/users/12").unwrap().get("id"), Some("12"));
+        assert!(pattern.matches("/users/me").is_none());
+        assert!(pattern.matches("/users/12/posts").is_none());
+
+        // The constraint is checked against the decoded value
+        assert_eq!(
+            pattern.matches("/users/%31%32").unwrap().get("id"),
+            Some("%31%32")
+        );
+        assert!(pattern.matches("/users/1%20").is_none());
+
+        let pattern = RoutePattern::parse("/blog/{page?}").unwrap();
+        assert_eq!(pattern.matches("/blog/2").unwrap().get("page"), Some("2"));
+        assert!(pattern.matches("/blog").unwrap().is_empty());
+        assert!(pattern.matches("/blog/").is_none());
+
+        let pattern = RoutePattern::parse("/docs/{*rest}").unwrap();
+        assert_eq!(
+            pattern.matches("/docs/guide/intro").unwrap().get("rest"),
+            Some("guide/intro")
+        );
+        assert!(pattern.matches("/docs").is_none());
+    }
+
+    #[test]
+    fn route_pattern_format_test() {
+        let pattern = RoutePattern::parse("/users/{id:u64}/{tab?}").unwrap();
+        let params = Params::from_iter([("id", "1"), ("tab", "posts")]);
+        assert_eq!(pattern.format(&params).unwrap(), "/users/1/posts");
+        assert_eq!(
+            pattern.format(&Params::from_iter([("id", "1")])).unwrap(),
+            "/users/1"
+        );
+        assert!(pattern.format(&Params::from_iter([("id", "me")])).is_err());
+
+        let pattern = RoutePattern::parse("/{page?}").unwrap();
+        assert_eq!(pattern.format(&Params::default()).unwrap(), "/");
+        assert!(pattern.matches("/").is_some());
+    }
+}
@@ -38,6 +38,11 @@ impl MethodRouter {
         self.0.iter().find(|route| route.method.matches(&m))
     }
 
+    /// Returns all the methods handled by this router.
+    pub(crate) fn methods(&self) -> Option<RouteMethod> {
+        self.0.iter().map(|route| route.method).reduce(|a, b| a | b)
+    }
+
     pub(crate) fn push(&mut self, route: Route) -> Result<(), RouteMethodConflict> {
         let method = route.method();
 
@@ -81,9 +86,9 @@ pub enum ServerRouterMatchError {
     #[error("route not found")]
     NotFound,
 
-    /// The path exists but doesn't match any method.
+    /// The path exists but doesn't match any method, contains the methods the path allows.
     #[error("method not allowed")]
-    MethodMismatch,
+    MethodMismatch(RouteMethod),
 
     /// Other match error.
     #[error(transparent)]
@@ -112,9 +117,11 @@ impl ServerRouter {
                 let id = mtch.value;
                 // SAFETY: If the id exists the route also exists
                 let method_router = self.id_to_route.get(id).unwrap();
-                let route_mtch = method_router
-                    .get(method)
-                    .ok_or_else(|| ServerRouterMatchError::MethodMismatch)?;
+                let route_mtch = method_router.get(method).ok_or_else(|| {
+                    // SAFETY: A method router is never empty
+                    let allowed = method_router.methods().unwrap();
+                    ServerRouterMatchError::MethodMismatch(allowed)
+                })?;
 
                 Ok(RouteMatch {
                     params: mtch.params.clone(),
@@ -126,6 +133,20 @@ impl ServerRouter {
         }
     }
 
+    /// Returns an iterator over all the routes.
+    pub fn routes(&self) -> impl Iterator<Item = &Route> {
+        self.id_to_route
+            .values()
+            .flat_map(|method_router| method_router.0.iter().map(|x| &x.route))
+    }
+
+    /// Returns a mutable iterator over all the routes.
+    pub(crate) fn routes_mut(&mut self) -> impl Iterator<Item = &mut Route> {
+        self.id_to_route
+            .values_mut()
+            .flat_map(|method_router| method_router.0.iter_mut().map(|x| &mut x.route))
+    }
+
     pub fn insert(&mut self, route: Route) -> Result<(), InsertServerRouteError> {
         let path = route.path().to_owned();
         match self.path_to_id.get(&path) {
@@ -264,7 +285,7 @@ mod tests {
 
         assert!(matches!(
             router.at("/path", RouteMethod::POST),
-            Err(ServerRouterMatchError::MethodMismatch)
+            Err(ServerRouterMatchError::MethodMismatch(allowed)) if allowed == RouteMethod::GET
         ));
     }
 
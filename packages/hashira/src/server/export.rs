@@ -0,0 +1,284 @@
+use crate::{
+    actions::has_embedded_csrf_token,
+    app::AppService,
+    components::{PageComponent, HASHIRA_PAGE_DATA_FILE, HASHIRA_PAGE_DATA_HEADER},
+    error::BoxError,
+    routing::{HandlerKind, Params, Route, RoutePattern},
+    types::BoxFuture,
+    web::{Body, Request},
+};
+use bytes::Bytes;
+use std::path::{Path, PathBuf};
+
+/// The function that returns the params used to export a page.
+#[derive(Clone, Copy)]
+pub(crate) struct StaticParams(fn() -> BoxFuture<Result<Vec<Params>, BoxError>>);
+
+impl StaticParams {
+    /// Returns the static params function of the given page.
+    pub fn of<COMP: PageComponent>() -> Self {
+        StaticParams(COMP::static_params)
+    }
+}
+
+/// Renders all the pages of the app and writes them as static files in the given directory.
+///
+/// Each page is written as `<path>/index.html` and its page data as `<path>/page-data.json`,
+/// the pages with dynamic routes are rendered once for each of the params returned
+/// by its `PageComponent::static_params`. The `public` directory next to the executable
+/// is copied into the static dir.
+///
+/// Pages rendering an `ActionForm` include the CSRF token of the client and are skipped.
+pub async fn export(service: &AppService, out_dir: impl AsRef<Path>) -> Result<(), BoxError> {
+    let out_dir = out_dir.as_ref();
+    log::info!("Exporting app to: {}", out_dir.display());
+
+    let paths = get_static_paths(service).await?;
+
+    for path in paths {
+        let Some(html) = render_page(service, &path, false).await? else {
+            log::warn!("Skipping `{path}`, pages rendering an `ActionForm` cannot be exported");
+            continue;
+        };
+
+        write_file(out_dir, &path, "index.html", html)?;
+
+        if let Some(page_data) = render_page(service, &path, true).await? {
+            write_file(out_dir, &path, HASHIRA_PAGE_DATA_FILE, page_data)?;
+        }
+
+        log::info!("Exported page: {path}");
+    }
+
+    let public_dir = get_current_dir()?.join("public");
+    if public_dir.exists() {
+        let static_dir = crate::env::get_static_dir();
+        let dest = out_dir.join(static_dir.trim_start_matches('/'));
+        copy_dir(&public_dir, &dest)?;
+    }
+
+    Ok(())
+}
+
+// Returns the paths of all the pages that can be exported.
+async fn get_static_paths(service: &AppService) -> Result<Vec<String>, BoxError> {
+    let mut paths = vec![];
+
+    let page_routes = service
+        .server_router()
+        .routes()
+        .filter(|route| route.extensions().get::<HandlerKind>() == Some(&HandlerKind::Page));
+
+    for route in page_routes {
+        if !is_dynamic_route(route.path()) {
+            paths.push(route.path().to_owned());
+            continue;
+        }
+
+        let params_list = match route.extensions().get::<StaticParams>() {
+            Some(StaticParams(static_params)) => static_params().await?,
+            None => vec![],
+        };
+
+        if params_list.is_empty() {
+            log::warn!(
+                "Skipping `{}`, dynamic routes require `static_params` to be exported",
+                route.path()
+            );
+        }
+
+        for params in params_list {
+            paths.push(get_static_path(route, &params)?);
+        }
+    }
+
+    Ok(paths)
+}
+
+fn is_dynamic_route(path: &str) -> bool {
+    RoutePattern::parse(path)
+        .map(|pattern| pattern.is_dynamic())
+        .unwrap_or(false)
+}
+
+// Replaces the params of the route path with the given values.
+fn get_static_path(route: &Route, params: &Params) -> Result<String, BoxError> {
+    let pattern = RoutePattern::parse(route.path())?;
+    let path = pattern
+        .format(params)
+        .map_err(|err| format!("failed to export route: {err}"))?;
+
+    Ok(path)
+}
+
+// Returns `None` if the page cannot be exported.
+async fn render_page(
+    service: &AppService,
+    path: &str,
+    page_data: bool,
+) -> Result<Option<Bytes>, BoxError> {
+    let mut builder = Request::builder().uri(path);
+
+    if page_data {
+        builder = builder.header(HASHIRA_PAGE_DATA_HEADER, "true");
+    }
+
+    let req = builder.body(Body::empty())?;
+    let res = service.handle(req).await;
+    let status = res.status();
+
+    if !status.is_success() {
+        return Err(format!("failed to export `{path}`, responded with status {status}").into());
+    }
+
+    let (parts, body) = res.into_parts();
+    let bytes = body.into_bytes().await?;
+
+    // The token of the client cannot be included in a static file
+    if has_embedded_csrf_token(&parts.extensions) {
+        return Ok(None);
+    }
+
+    Ok(Some(bytes))
+}
+
+fn write_file(
+    out_dir: &Path,
+    path: &str,
+    file_name: &str,
+    contents: Bytes,
+) -> Result<(), BoxError> {
+    let dir = out_dir.join(path.trim_start_matches('/'));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(file_name), contents)?;
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), BoxError> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn get_current_dir() -> Result<PathBuf, BoxError> {
+    let mut current_dir = std::env::current_exe()?;
+    current_dir.pop();
+    Ok(current_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export, get_static_path};
+    use crate::{
+        app::{App, RenderContext},
+        components::{PageComponent, HASHIRA_PAGE_DATA_FILE},
+        routing::{Params, Route},
+        types::BoxFuture,
+        web::{Body, Response},
+    };
+    use yew::{function_component, html::ChildrenProps};
+
+    #[test]
+    fn static_path_test() {
+        async fn noop() {}
+
+        let route = Route::get("/users/:id/posts/:post", noop);
+        let params = Params::from_iter([("id", "1"), ("post", "hello")]);
+        assert_eq!(
+            get_static_path(&route, &params).unwrap(),
+            "/users/1/posts/hello"
+        );
+
+        let route = Route::get("/docs/*rest", noop);
+        let params = Params::from_iter([("rest", "guide/intro")]);
+        assert_eq!(
+            get_static_path(&route, &params).unwrap(),
+            "/docs/guide/intro"
+        );
+
+        let route = Route::get("/users/{id:u64}/{tab?}", noop);
+        let params = Params::from_iter([("id", "1")]);
+        assert_eq!(get_static_path(&route, &params).unwrap(), "/users/1");
+
+        let route = Route::get("/users/:id", noop);
+        assert!(get_static_path(&route, &Params::default()).is_err());
+        assert!(get_static_path(&route, &Params::from_iter([("id", "a/b")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn export_test() {
+        #[function_component]
+        fn HomePage() -> yew::Html {
+            yew::html! { "home" }
+        }
+
+        #[function_component]
+        fn UserPage() -> yew::Html {
+            yew::html! { "user" }
+        }
+
+        crate::impl_page_component!(HomePage, "/");
+
+        impl PageComponent for UserPage {
+            fn route() -> Option<&'static str> {
+                Some("/users/:id")
+            }
+
+            fn static_params() -> BoxFuture<crate::Result<Vec<Params>>> {
+                Box::pin(async {
+                    Ok(vec![
+                        Params::from_iter([("id", "1")]),
+                        Params::from_iter([("id", "2")]),
+                    ])
+                })
+            }
+
+            fn render<BASE>(ctx: RenderContext, _: Body) -> BoxFuture<crate::Result<Response>>
+            where
+                BASE: yew::BaseComponent<Properties = ChildrenProps>,
+            {
+                Box::pin(async move { Ok(ctx.render::<Self, BASE>().await) })
+            }
+        }
+
+        let service = App::<Base>::new()
+            .page::<HomePage>()
+            .page::<UserPage>()
+            .build();
+
+        let out_dir = std::env::temp_dir().join(format!("hashira_export_{}", std::process::id()));
+        export(&service, &out_dir).await.unwrap();
+
+        let home = std::fs::read_to_string(out_dir.join("index.html")).unwrap();
+        assert!(home.contains("home"));
+
+        for id in ["1", "2"] {
+            let dir = out_dir.join("users").join(id);
+            let html = std::fs::read_to_string(dir.join("index.html")).unwrap();
+            assert!(html.contains("user"));
+
+            let page_data = std::fs::read_to_string(dir.join(HASHIRA_PAGE_DATA_FILE)).unwrap();
+            assert!(serde_json::from_str::<serde_json::Value>(&page_data).is_ok());
+        }
+
+        std::fs::remove_dir_all(out_dir).unwrap();
+    }
+
+    #[function_component]
+    fn Base(props: &ChildrenProps) -> yew::Html {
+        yew::html! {
+            <>{for props.children.iter()}</>
+        }
+    }
+}
@@ -1,14 +1,24 @@
 pub mod error;
 mod links;
 mod meta;
+mod render_cache;
 mod scripts;
+mod static_regeneration;
 
 pub use links::*;
 pub use meta::*;
+pub use render_cache::*;
 pub use scripts::*;
+pub use static_regeneration::*;
 
 #[cfg(not(feature = "client"))]
 mod render;
 
 #[cfg(not(feature = "client"))]
 pub use render::*;
+
+#[cfg(not(feature = "client"))]
+mod export;
+
+#[cfg(not(feature = "client"))]
+pub use export::*;
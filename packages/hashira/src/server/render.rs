@@ -1,12 +1,13 @@
 use super::{error::RenderError, Metadata, PageLinks, PageScripts};
+use crate::actions::ActionFlash;
 use crate::app::page_head::PageHead;
 use crate::app::router::PageRouterWrapper;
 use crate::app::RequestContext;
 use crate::components::id::PageId;
 use crate::components::{
-    Page, PageComponent, PageData, PageError, PageProps, HASHIRA_CONTENT_MARKER,
-    HASHIRA_LINKS_MARKER, HASHIRA_META_MARKER, HASHIRA_PAGE_DATA, HASHIRA_ROOT,
-    HASHIRA_SCRIPTS_MARKER, HASHIRA_TITLE_MARKER,
+    DeferredValues, Page, PageComponent, PageData, PageError, PageProps, SuspenseStreamer,
+    HASHIRA_CONTENT_MARKER, HASHIRA_LINKS_MARKER, HASHIRA_META_MARKER, HASHIRA_PAGE_DATA,
+    HASHIRA_ROOT, HASHIRA_SCRIPTS_MARKER, HASHIRA_TITLE_MARKER,
 };
 use crate::context::ServerContext;
 use crate::error::BoxError;
@@ -15,7 +16,7 @@ use crate::types::TryBoxStream;
 use bytes::Bytes;
 use futures::{stream, StreamExt, TryStreamExt};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use yew::{
     function_component,
     html::{ChildrenProps, ChildrenRenderer},
@@ -32,6 +33,9 @@ pub(crate) struct RenderPageOptions {
     // The context of the current request
     pub request_context: RequestContext,
 
+    // The data of the queries used by the page
+    pub queries: HashMap<String, serde_json::Value>,
+
     // The router used to render the page
     pub router: PageRouterWrapper,
 
@@ -65,6 +69,7 @@ where
         router,
         error_router,
         request_context,
+        queries,
     } = options;
 
     // The base layout
@@ -92,26 +97,11 @@ where
         }
     }
 
-    let props_json = serde_json::to_value(props).map_err(RenderError::InvalidProps)?;
-    let component_id = PageId::of::<COMP>();
-    let page_error = {
-        match request_context.error() {
-            Some(e) => Some(PageError {
-                status: e.status(),
-                message: e.try_get_message().await,
-            }),
-            None => None,
-        }
-    };
-
     // The data inserted in the html
-    let page_data = PageData {
-        id: component_id,
-        props: props_json,
-        uri: request_context.request().uri().clone(),
-        error: page_error,
-        params: request_context.params().clone(),
-    };
+    let (page_data, deferred) = create_page_data::<COMP>(props, &request_context, queries).await?;
+
+    // Renders the `StreamingSuspense` boundaries after the page content
+    let (streamer, resolved_chunks) = SuspenseStreamer::new();
 
     // The props passed to the container page
     let page_props = PageProps {
@@ -122,10 +112,14 @@ where
         // FIXME: Unnecessary?
         // We need to clone when using hooks
         #[cfg(feature = "hooks")]
-        server_context: ServerContext::new(Some(request_context.clone())),
+        server_context: ServerContext::new(Some(request_context.clone()))
+            .with_streamer(streamer.clone())
+            .with_deferred(deferred.clone()),
 
         #[cfg(not(feature = "hooks"))]
-        server_context: ServerContext::new(Some(request_context)),
+        server_context: ServerContext::new(Some(request_context))
+            .with_streamer(streamer.clone())
+            .with_deferred(deferred.clone()),
     };
 
     let (title, metadata, links, scripts) = head.into_parts();
@@ -153,6 +147,14 @@ where
     })
     // content
     .chain(page_html)
+    // The suspense boundaries and deferred values resolved while and after the content was rendered
+    .chain(
+        stream::select(
+            streamer.into_stream(resolved_chunks),
+            deferred.into_stream(),
+        )
+        .map(Ok),
+    )
     .chain(stream::once(async move {
         // After content
         render_after_content_markers(after_content_html, after_content, page_data)
@@ -188,6 +190,42 @@ where
     Ok(Box::pin(html_stream))
 }
 
+/// Creates the data of the given page for the current request and returns the `Deferred` values of the props.
+pub(crate) async fn create_page_data<COMP>(
+    props: COMP::Properties,
+    request_context: &RequestContext,
+    queries: HashMap<String, serde_json::Value>,
+) -> Result<(PageData, DeferredValues), RenderError>
+where
+    COMP: PageComponent,
+    COMP::Properties: Serialize,
+{
+    let (props_json, deferred) = DeferredValues::collect(|| serde_json::to_value(props));
+    let props_json = props_json.map_err(RenderError::InvalidProps)?;
+    let component_id = PageId::of::<COMP>();
+    let page_error = {
+        match request_context.error() {
+            Some(e) => Some(PageError {
+                status: e.status(),
+                message: e.try_get_message().await,
+            }),
+            None => None,
+        }
+    };
+
+    let page_data = PageData {
+        id: component_id,
+        props: props_json,
+        uri: request_context.request().uri().clone(),
+        error: page_error,
+        params: request_context.params().clone(),
+        action_flash: ActionFlash::from_request(request_context.request()),
+        queries,
+    };
+
+    Ok((page_data, deferred))
+}
+
 /// Renders the given component inside the given root as a html string.
 pub(crate) async fn render_page_to_html<COMP, ROOT>(
     props: COMP::Properties,
@@ -0,0 +1,430 @@
+use crate::{
+    actions::has_embedded_csrf_token,
+    app::CachePolicy,
+    components::HASHIRA_PAGE_DATA_HEADER,
+    routing::Params,
+    web::{Body, IntoResponse, Request, Response},
+};
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderName, StatusCode};
+use indexmap::IndexMap;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The key of a cached page.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderCacheKey {
+    path: String,
+    params: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    page_data: bool,
+}
+
+impl RenderCacheKey {
+    /// Returns the path and query of the cached page.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the params that matched the route of the page.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// Returns the values of the headers the cached page varies on.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns `true` if the cached response is the page data and not the html.
+    pub fn is_page_data(&self) -> bool {
+        self.page_data
+    }
+}
+
+/// A rendered page stored in a cache.
+#[derive(Debug, Clone)]
+pub struct CachedPage {
+    /// The status code of the response.
+    pub status: StatusCode,
+
+    /// The headers of the response.
+    pub headers: HeaderMap,
+
+    /// The body of the response.
+    pub body: Bytes,
+
+    /// The cache tags of the page.
+    pub tags: Vec<String>,
+
+    /// When this page should be removed from the cache.
+    pub expires_at: Instant,
+}
+
+impl CachedPage {
+    /// Returns `true` if this page should be not longer used.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+
+    /// Creates a response from this page.
+    pub fn to_response(&self) -> Response {
+        let mut res = Response::new(Body::from(self.body.clone()));
+        *res.status_mut() = self.status;
+        *res.headers_mut() = self.headers.clone();
+        res
+    }
+}
+
+/// A storage for the rendered pages.
+pub trait RenderCache: Send + Sync + 'static {
+    /// Returns the page with the given key.
+    fn get(&self, key: &RenderCacheKey) -> Option<CachedPage>;
+
+    /// Stores a page.
+    fn insert(&self, key: RenderCacheKey, page: CachedPage);
+
+    /// Removes all the pages with the given path, including the ones with query.
+    fn invalidate_path(&self, path: &str);
+
+    /// Removes all the pages with any of the given tags.
+    fn invalidate_tags(&self, tags: &[&str]);
+
+    /// Removes all the pages.
+    fn clear(&self);
+}
+
+/// An in-memory `RenderCache` that removes the least recently used pages when is full.
+pub struct InMemoryRenderCache {
+    capacity: usize,
+    pages: Mutex<IndexMap<RenderCacheKey, CachedPage>>,
+}
+
+impl InMemoryRenderCache {
+    /// Constructs a cache that can store the given number of pages.
+    ///
+    /// # Panic
+    /// If the capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+
+        InMemoryRenderCache {
+            capacity,
+            pages: Mutex::new(IndexMap::new()),
+        }
+    }
+
+    /// Returns the number of pages in the cache.
+    pub fn len(&self) -> usize {
+        self.pages.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RenderCache for InMemoryRenderCache {
+    fn get(&self, key: &RenderCacheKey) -> Option<CachedPage> {
+        let mut pages = self.pages.lock().unwrap();
+        let page = pages.shift_remove(key)?;
+
+        if page.is_expired() {
+            return None;
+        }
+
+        // Move to the end as the most recently used
+        pages.insert(key.clone(), page.clone());
+        Some(page)
+    }
+
+    fn insert(&self, key: RenderCacheKey, page: CachedPage) {
+        let mut pages = self.pages.lock().unwrap();
+        pages.shift_remove(&key);
+
+        while pages.len() >= self.capacity {
+            pages.shift_remove_index(0);
+        }
+
+        pages.insert(key, page);
+    }
+
+    fn invalidate_path(&self, path: &str) {
+        let mut pages = self.pages.lock().unwrap();
+        pages.retain(|key, _| {
+            let key_path = key
+                .path
+                .split_once('?')
+                .map(|(p, _)| p)
+                .unwrap_or(&key.path);
+            key_path != path
+        });
+    }
+
+    fn invalidate_tags(&self, tags: &[&str]) {
+        let mut pages = self.pages.lock().unwrap();
+        pages.retain(|_, page| !page.tags.iter().any(|t| tags.contains(&t.as_str())));
+    }
+
+    fn clear(&self) {
+        self.pages.lock().unwrap().clear();
+    }
+}
+
+/// Caches the rendered pages of the app.
+///
+/// Only the pages with a public `CachePolicy` with a `max-age` that don't set cookies are stored,
+/// this can be injected in server actions to invalidate the cached pages.
+#[derive(Clone)]
+pub struct PageCache {
+    cache: Arc<dyn RenderCache>,
+    vary_headers: Arc<Vec<HeaderName>>,
+}
+
+impl PageCache {
+    /// Constructs a page cache using the given storage.
+    pub fn new<C: RenderCache>(cache: C) -> Self {
+        PageCache {
+            cache: Arc::new(cache),
+            vary_headers: Default::default(),
+        }
+    }
+
+    /// Constructs a page cache stored in memory with the given capacity.
+    pub fn in_memory(capacity: usize) -> Self {
+        PageCache::new(InMemoryRenderCache::new(capacity))
+    }
+
+    /// Adds a header which value is used as part of the cache key, like `Accept-Language`.
+    pub fn vary<K>(mut self, header: K) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: std::fmt::Debug,
+    {
+        let name = HeaderName::try_from(header).expect("invalid header name");
+        Arc::make_mut(&mut self.vary_headers).push(name);
+        self
+    }
+
+    /// Removes all the cached pages with the given path.
+    pub fn invalidate_path(&self, path: &str) {
+        self.cache.invalidate_path(path);
+    }
+
+    /// Removes all the cached pages with any of the given tags.
+    pub fn invalidate_tags(&self, tags: &[&str]) {
+        self.cache.invalidate_tags(tags);
+    }
+
+    /// Removes all the cached pages.
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    /// Returns the key for the given request.
+    pub fn key(&self, req: &Request<()>, params: &Params) -> RenderCacheKey {
+        let path = req
+            .uri()
+            .path_and_query()
+            .map(|x| x.as_str().to_owned())
+            .unwrap_or_else(|| req.uri().path().to_owned());
+
+        let params = params
+            .iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        let headers = self
+            .vary_headers
+            .iter()
+            .map(|name| {
+                let value = req
+                    .headers()
+                    .get(name)
+                    .and_then(|x| x.to_str().ok())
+                    .unwrap_or_default();
+
+                (name.as_str().to_owned(), value.to_owned())
+            })
+            .collect();
+
+        let page_data = req.headers().contains_key(HASHIRA_PAGE_DATA_HEADER);
+
+        RenderCacheKey {
+            path,
+            params,
+            headers,
+            page_data,
+        }
+    }
+
+    /// Returns the cached response for the given key.
+    pub fn get(&self, key: &RenderCacheKey) -> Option<Response> {
+        self.cache.get(key).map(|page| page.to_response())
+    }
+
+    /// Stores the response if its cache policy allow it and returns the response.
+    pub async fn store(&self, key: RenderCacheKey, res: Response) -> Response {
+        // Only public pages that can be reused are stored
+        let policy = match CachePolicy::from_headers(res.headers()) {
+            Some(policy) if !policy.is_private() && res.status().is_success() => policy,
+            _ => return res,
+        };
+
+        let max_age = match policy.get_max_age() {
+            Some(max_age) if max_age > Duration::ZERO => max_age,
+            _ => return res,
+        };
+
+        // The cookies are only for the client that made the request
+        if res.headers().contains_key(header::SET_COOKIE) {
+            return res;
+        }
+
+        let (parts, body) = res.into_parts();
+        let body = match body.into_bytes().await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("failed to read page body: {err}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        // The page contains the token of the client
+        if has_embedded_csrf_token(&parts.extensions) {
+            return Response::from_parts(parts, Body::from(body));
+        }
+
+        let page = CachedPage {
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+            tags: policy.tags().to_vec(),
+            expires_at: Instant::now() + max_age,
+        };
+
+        self.cache.insert(key, page);
+        Response::from_parts(parts, Body::from(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedPage, InMemoryRenderCache, PageCache, RenderCache};
+    use crate::routing::Params;
+    use crate::web::{Body, Request, Response};
+    use bytes::Bytes;
+    use http::{header, HeaderMap, HeaderValue, StatusCode};
+    use std::time::{Duration, Instant};
+
+    fn page(tags: &[&str]) -> CachedPage {
+        CachedPage {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"hello"),
+            tags: tags.iter().map(|s| s.to_string()).collect(),
+            expires_at: Instant::now() + Duration::from_secs(60),
+        }
+    }
+
+    fn req(path: &str) -> Request<()> {
+        Request::builder().uri(path).body(()).unwrap()
+    }
+
+    #[test]
+    fn lru_test() {
+        let page_cache = PageCache::in_memory(2);
+        let cache = InMemoryRenderCache::new(2);
+
+        let a = page_cache.key(&req("/a"), &Params::default());
+        let b = page_cache.key(&req("/b"), &Params::default());
+        let c = page_cache.key(&req("/c"), &Params::default());
+
+        cache.insert(a.clone(), page(&[]));
+        cache.insert(b.clone(), page(&[]));
+
+        // `a` is now the most recently used
+        assert!(cache.get(&a).is_some());
+
+        cache.insert(c.clone(), page(&[]));
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+    }
+
+    #[test]
+    fn invalidate_test() {
+        let page_cache = PageCache::in_memory(10);
+        let cache = InMemoryRenderCache::new(10);
+
+        let a = page_cache.key(&req("/a?page=1"), &Params::default());
+        let b = page_cache.key(&req("/b"), &Params::default());
+        let c = page_cache.key(&req("/c"), &Params::default());
+
+        cache.insert(a.clone(), page(&[]));
+        cache.insert(b.clone(), page(&["todos"]));
+        cache.insert(c.clone(), page(&["users"]));
+
+        cache.invalidate_path("/a");
+        assert!(cache.get(&a).is_none());
+
+        cache.invalidate_tags(&["todos"]);
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn store_skips_set_cookie_test() {
+        let page_cache = PageCache::in_memory(10);
+
+        let mut res = Response::new(Body::from("session"));
+        res.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=60"),
+        );
+        res.headers_mut()
+            .insert(header::SET_COOKIE, HeaderValue::from_static("session=1"));
+
+        let a = page_cache.key(&req("/a"), &Params::default());
+        let res = page_cache.store(a.clone(), res).await;
+        assert!(res.headers().contains_key(header::SET_COOKIE));
+        assert!(page_cache.get(&a).is_none());
+
+        let mut res = Response::new(Body::from("public"));
+        res.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=60"),
+        );
+
+        let b = page_cache.key(&req("/b"), &Params::default());
+        page_cache.store(b.clone(), res).await;
+        assert!(page_cache.get(&b).is_some());
+    }
+
+    #[test]
+    fn vary_headers_test() {
+        let page_cache = PageCache::in_memory(10).vary("accept-language");
+
+        let en = Request::builder()
+            .uri("/a")
+            .header("accept-language", "en")
+            .body(())
+            .unwrap();
+
+        let es = Request::builder()
+            .uri("/a")
+            .header("accept-language", "es")
+            .body(())
+            .unwrap();
+
+        let params = Params::default();
+        assert_ne!(page_cache.key(&en, &params), page_cache.key(&es, &params));
+        assert_eq!(page_cache.key(&en, &params), page_cache.key(&en, &params));
+    }
+}
@@ -0,0 +1,642 @@
+use crate::{
+    actions::has_embedded_csrf_token,
+    app::CachePolicy,
+    components::{HASHIRA_CACHE_TAGS_HEADER, HASHIRA_PAGE_DATA_FILE, HASHIRA_PAGE_DATA_HEADER},
+    error::BoxError,
+    web::{Body, IntoResponse, Request, Response},
+};
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use indexmap::IndexMap;
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// The interval after which a stored page is regenerated, stored in the page route.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "client", allow(dead_code))]
+pub(crate) struct Revalidate(pub Duration);
+
+/// A rendered output of a page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PageArtifact {
+    /// The `html` of the page.
+    Html,
+
+    /// The `json` page data used by the client router.
+    PageData,
+}
+
+impl PageArtifact {
+    /// Returns the artifact requested by the given request.
+    pub fn from_request(req: &Request<()>) -> Self {
+        if req.headers().contains_key(HASHIRA_PAGE_DATA_HEADER) {
+            PageArtifact::PageData
+        } else {
+            PageArtifact::Html
+        }
+    }
+
+    /// Returns the name of the file where this artifact is stored, the same used by the static export.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            PageArtifact::Html => "index.html",
+            PageArtifact::PageData => HASHIRA_PAGE_DATA_FILE,
+        }
+    }
+
+    /// Returns a request for this artifact of the given page without any client data or query string,
+    /// stored pages are shared by all the clients so are rendered with this request.
+    pub(crate) fn page_request(&self, req: &Request<()>) -> Request<()> {
+        let mut page_req = Request::new(());
+        *page_req.method_mut() = Method::GET;
+        *page_req.uri_mut() = req.uri().path().parse().expect("invalid page path");
+        *page_req.version_mut() = req.version();
+
+        let headers = page_req.headers_mut();
+        if let Some(host) = req.headers().get(header::HOST) {
+            headers.insert(header::HOST, host.clone());
+        }
+
+        if *self == PageArtifact::PageData {
+            headers.insert(HASHIRA_PAGE_DATA_HEADER, HeaderValue::from_static("true"));
+        }
+
+        page_req
+    }
+
+    /// Returns the content type of this artifact.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PageArtifact::Html => "text/html; charset=utf-8",
+            PageArtifact::PageData => "application/json",
+        }
+    }
+}
+
+/// The headers of the rendered page that are stored with the page.
+const STORED_HEADERS: [HeaderName; 2] = [
+    header::CACHE_CONTROL,
+    HeaderName::from_static(HASHIRA_CACHE_TAGS_HEADER),
+];
+
+/// A page stored in a `PageStore`.
+#[derive(Debug, Clone)]
+pub struct StoredPage {
+    /// The rendered content.
+    pub body: Bytes,
+
+    /// The caching headers of the rendered page, like `Cache-Control`.
+    pub headers: HeaderMap,
+
+    /// When this page was rendered.
+    pub generated_at: SystemTime,
+}
+
+impl StoredPage {
+    /// Constructs a page rendered now with the given body and the stored headers of the response.
+    pub fn new(body: Bytes, headers: &HeaderMap) -> Self {
+        let headers = STORED_HEADERS
+            .iter()
+            .flat_map(|name| {
+                headers
+                    .get_all(name)
+                    .iter()
+                    .map(move |value| (name.clone(), value.clone()))
+            })
+            .collect();
+
+        StoredPage {
+            body,
+            headers,
+            generated_at: SystemTime::now(),
+        }
+    }
+
+    /// Returns `true` if this page was rendered more than the given duration ago.
+    pub fn is_stale(&self, revalidate: Duration) -> bool {
+        match self.generated_at.elapsed() {
+            Ok(elapsed) => elapsed >= revalidate,
+            Err(_) => false,
+        }
+    }
+
+    /// Creates a response from this page.
+    pub fn to_response(&self, artifact: PageArtifact) -> Response {
+        let mut res = Response::new(Body::from(self.body.clone()));
+        *res.status_mut() = StatusCode::OK;
+        *res.headers_mut() = self.headers.clone();
+
+        let headers = res.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(artifact.content_type()),
+        );
+        headers.insert(
+            header::VARY,
+            HeaderValue::from_static(HASHIRA_PAGE_DATA_HEADER),
+        );
+
+        res
+    }
+}
+
+/// A storage for the rendered pages.
+///
+/// The pages are keyed by its path, like `/users/1`.
+pub trait PageStore: Send + Sync + 'static {
+    /// Returns the page stored for the given path.
+    fn get(&self, path: &str, artifact: PageArtifact) -> Result<Option<StoredPage>, BoxError>;
+
+    /// Stores the rendered page for the given path.
+    fn put(&self, path: &str, artifact: PageArtifact, page: StoredPage) -> Result<(), BoxError>;
+
+    /// Removes all the artifacts stored for the given path.
+    fn remove(&self, path: &str) -> Result<(), BoxError>;
+}
+
+/// A `PageStore` that keeps the pages in memory and removes the least recently used pages when is full.
+pub struct InMemoryPageStore {
+    capacity: usize,
+    pages: Mutex<IndexMap<(String, PageArtifact), StoredPage>>,
+}
+
+impl InMemoryPageStore {
+    /// Constructs a store that can keep the given number of pages, each artifact counts as a page.
+    ///
+    /// # Panic
+    /// If the capacity is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+
+        InMemoryPageStore {
+            capacity,
+            pages: Mutex::new(IndexMap::new()),
+        }
+    }
+
+    /// Returns the number of pages in the store.
+    pub fn len(&self) -> usize {
+        self.pages.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PageStore for InMemoryPageStore {
+    fn get(&self, path: &str, artifact: PageArtifact) -> Result<Option<StoredPage>, BoxError> {
+        let mut pages = self.pages.lock().unwrap();
+        let key = (path.to_owned(), artifact);
+        let Some(page) = pages.shift_remove(&key) else {
+            return Ok(None);
+        };
+
+        // Move to the end as the most recently used
+        pages.insert(key, page.clone());
+        Ok(Some(page))
+    }
+
+    fn put(&self, path: &str, artifact: PageArtifact, page: StoredPage) -> Result<(), BoxError> {
+        let mut pages = self.pages.lock().unwrap();
+        let key = (path.to_owned(), artifact);
+        pages.shift_remove(&key);
+
+        while pages.len() >= self.capacity {
+            pages.shift_remove_index(0);
+        }
+
+        pages.insert(key, page);
+        Ok(())
+    }
+
+    fn remove(&self, path: &str) -> Result<(), BoxError> {
+        self.pages.lock().unwrap().retain(|(p, _), _| p != path);
+        Ok(())
+    }
+}
+
+/// A `PageStore` that writes the pages in a directory.
+///
+/// The pages are stored using the same layout than the static export, `<path>/index.html`
+/// and `<path>/page-data.json`, so the output of an export can be used as the initial pages.
+/// The headers of each page are stored next to it in a `.<file name>.headers` file.
+pub struct FsPageStore {
+    dir: PathBuf,
+}
+
+impl FsPageStore {
+    /// Constructs a store that writes the pages in the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FsPageStore { dir: dir.into() }
+    }
+
+    // Returns the directory of the given page, ensuring is inside the store directory.
+    fn page_dir(&self, path: &str) -> Result<PathBuf, BoxError> {
+        let relative = Path::new(path.trim_start_matches('/'));
+
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(format!("invalid page path: `{path}`").into());
+        }
+
+        Ok(self.dir.join(relative))
+    }
+}
+
+// Returns the file where the headers of the given artifact are stored.
+fn headers_file_name(artifact: PageArtifact) -> String {
+    format!(".{}.headers", artifact.file_name())
+}
+
+impl PageStore for FsPageStore {
+    fn get(&self, path: &str, artifact: PageArtifact) -> Result<Option<StoredPage>, BoxError> {
+        let dir = self.page_dir(path)?;
+        let file = dir.join(artifact.file_name());
+
+        if !file.exists() {
+            return Ok(None);
+        }
+
+        let body = std::fs::read(&file)?;
+        let generated_at = std::fs::metadata(&file)?.modified()?;
+
+        // The pages of a static export have no headers
+        let mut headers = HeaderMap::new();
+        let headers_file = dir.join(headers_file_name(artifact));
+        if headers_file.exists() {
+            for line in std::fs::read_to_string(headers_file)?.lines() {
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::try_from(name.trim()),
+                    HeaderValue::try_from(value.trim()),
+                ) {
+                    headers.append(name, value);
+                }
+            }
+        }
+
+        Ok(Some(StoredPage {
+            body: Bytes::from(body),
+            headers,
+            generated_at,
+        }))
+    }
+
+    fn put(&self, path: &str, artifact: PageArtifact, page: StoredPage) -> Result<(), BoxError> {
+        let dir = self.page_dir(path)?;
+        std::fs::create_dir_all(&dir)?;
+
+        let mut headers = String::new();
+        for (name, value) in page.headers.iter() {
+            if let Ok(value) = value.to_str() {
+                headers.push_str(&format!("{name}: {value}\n"));
+            }
+        }
+
+        // The headers are written first, so a page is never served with stale headers
+        let headers_file = dir.join(headers_file_name(artifact));
+        let temp_headers_file = dir.join(format!("{}.tmp", headers_file_name(artifact)));
+        std::fs::write(&temp_headers_file, headers)?;
+        std::fs::rename(temp_headers_file, headers_file)?;
+
+        // We write to a temporary file first to not serve a partially written page
+        let file = dir.join(artifact.file_name());
+        let temp_file = dir.join(format!(".{}.tmp", artifact.file_name()));
+        std::fs::write(&temp_file, page.body)?;
+        std::fs::rename(temp_file, file)?;
+        Ok(())
+    }
+
+    fn remove(&self, path: &str) -> Result<(), BoxError> {
+        let dir = self.page_dir(path)?;
+
+        for artifact in [PageArtifact::Html, PageArtifact::PageData] {
+            for file_name in [artifact.file_name().to_owned(), headers_file_name(artifact)] {
+                let file = dir.join(file_name);
+                if file.exists() {
+                    std::fs::remove_file(file)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Serves the pages that declare a `revalidate` interval from a `PageStore`.
+///
+/// The first request renders and stores the page, the next requests are served
+/// from the store and after the interval passes the stale page is served while
+/// is regenerated in the background.
+///
+/// Like the exported pages, the stored pages are rendered without the query string of the request.
+#[derive(Clone)]
+pub struct StaticRegeneration {
+    store: Arc<dyn PageStore>,
+    regenerating: Arc<Mutex<HashSet<(String, PageArtifact)>>>,
+}
+
+impl StaticRegeneration {
+    /// Constructs a `StaticRegeneration` using the given store.
+    pub fn new<S: PageStore>(store: S) -> Self {
+        StaticRegeneration {
+            store: Arc::new(store),
+            regenerating: Default::default(),
+        }
+    }
+
+    /// Constructs a `StaticRegeneration` that stores the pages in the given directory.
+    pub fn on_disk(dir: impl Into<PathBuf>) -> Self {
+        StaticRegeneration::new(FsPageStore::new(dir))
+    }
+
+    /// Constructs a `StaticRegeneration` that stores the given number of pages in memory.
+    pub fn in_memory(capacity: usize) -> Self {
+        StaticRegeneration::new(InMemoryPageStore::new(capacity))
+    }
+
+    /// Returns the stored page for the given path.
+    pub async fn get(&self, key: &str, artifact: PageArtifact) -> Option<StoredPage> {
+        let store = self.store.clone();
+        let page_key = key.to_owned();
+
+        match run_blocking(move || store.get(&page_key, artifact)).await {
+            Ok(page) => page,
+            Err(err) => {
+                log::error!("failed to read stored page `{key}`: {err}");
+                None
+            }
+        }
+    }
+
+    /// Stores the response if was successful and returns the response.
+    ///
+    /// Private responses, responses setting cookies and pages rendering the CSRF token
+    /// of the client are never stored.
+    pub async fn store(&self, key: &str, artifact: PageArtifact, res: Response) -> Response {
+        let is_private = CachePolicy::from_headers(res.headers())
+            .map(|policy| policy.is_private())
+            .unwrap_or_default();
+
+        if !res.status().is_success()
+            || is_private
+            || res.headers().contains_key(header::SET_COOKIE)
+        {
+            return res;
+        }
+
+        let (parts, body) = res.into_parts();
+        let body = match body.into_bytes().await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("failed to read page body: {err}");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+        // The page contains the token of the client
+        if has_embedded_csrf_token(&parts.extensions) {
+            return Response::from_parts(parts, Body::from(body));
+        }
+
+        let store = self.store.clone();
+        let page_key = key.to_owned();
+        let page = StoredPage::new(body.clone(), &parts.headers);
+
+        if let Err(err) = run_blocking(move || store.put(&page_key, artifact, page)).await {
+            log::error!("failed to store page `{key}`: {err}");
+        }
+
+        Response::from_parts(parts, Body::from(body))
+    }
+
+    /// Removes the stored pages for the given path, the next request will render it again.
+    pub async fn invalidate(&self, path: &str) {
+        let store = self.store.clone();
+        let page_path = path.to_owned();
+
+        if let Err(err) = run_blocking(move || store.remove(&page_path)).await {
+            log::error!("failed to remove stored page `{path}`: {err}");
+        }
+    }
+
+    /// Marks the page as being regenerated, returns `None` if is already being regenerated.
+    ///
+    /// The regeneration ends when the returned guard is dropped.
+    pub(crate) fn start_regeneration(
+        &self,
+        key: &str,
+        artifact: PageArtifact,
+    ) -> Option<RegenerationGuard> {
+        let entry = (key.to_owned(), artifact);
+        if !self.regenerating.lock().unwrap().insert(entry.clone()) {
+            return None;
+        }
+
+        Some(RegenerationGuard {
+            regenerating: self.regenerating.clone(),
+            entry,
+        })
+    }
+}
+
+/// Marks the regeneration of a page as completed when dropped,
+/// even if the regeneration panics.
+pub(crate) struct RegenerationGuard {
+    regenerating: Arc<Mutex<HashSet<(String, PageArtifact)>>>,
+    entry: (String, PageArtifact),
+}
+
+impl Drop for RegenerationGuard {
+    fn drop(&mut self) {
+        // The lock may be poisoned if we are dropped during a panic
+        let mut regenerating = match self.regenerating.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        regenerating.remove(&self.entry);
+    }
+}
+
+// Runs the blocking store operation outside the async runtime, if any.
+async fn run_blocking<F, T>(f: F) -> Result<T, BoxError>
+where
+    F: FnOnce() -> Result<T, BoxError> + Send + 'static,
+    T: Send + 'static,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return tokio::task::spawn_blocking(f).await?;
+        }
+    }
+
+    f()
+}
+
+/// Runs the given future in the background.
+#[cfg_attr(feature = "client", allow(dead_code))]
+pub(crate) fn spawn_background<F>(fut: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    #[cfg(target_arch = "wasm32")]
+    {
+        wasm_bindgen_futures::spawn_local(fut);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // We use the current runtime if any, otherwise we block on other thread
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(fut);
+            }
+            Err(_) => {
+                std::thread::spawn(move || futures::executor::block_on(fut));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FsPageStore, InMemoryPageStore, PageArtifact, PageStore, StaticRegeneration, StoredPage,
+    };
+    use crate::{
+        components::HASHIRA_CACHE_TAGS_HEADER,
+        web::{Body, Request, Response},
+    };
+    use bytes::Bytes;
+    use http::{header, HeaderMap, HeaderValue};
+    use std::time::Duration;
+
+    fn page(body: &'static [u8]) -> StoredPage {
+        StoredPage::new(Bytes::from_static(body), &HeaderMap::new())
+    }
+
+    #[test]
+    fn fs_store_test() {
+        let dir = std::env::temp_dir().join(format!("hashira_pages_{}", std::process::id()));
+        let store = FsPageStore::new(&dir);
+
+        store
+            .put("/users/1", PageArtifact::Html, page(b"<h1>1</h1>"))
+            .unwrap();
+
+        let page = store.get("/users/1", PageArtifact::Html).unwrap().unwrap();
+        assert_eq!(page.body, Bytes::from_static(b"<h1>1</h1>"));
+        assert!(!page.is_stale(Duration::from_secs(60)));
+        assert!(dir.join("users/1/index.html").exists());
+
+        assert!(store
+            .get("/users/1", PageArtifact::PageData)
+            .unwrap()
+            .is_none());
+        assert!(store.get("/users/../secret", PageArtifact::Html).is_err());
+
+        store.remove("/users/1").unwrap();
+        assert!(store.get("/users/1", PageArtifact::Html).unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn fs_store_headers_test() {
+        let dir =
+            std::env::temp_dir().join(format!("hashira_headers_pages_{}", std::process::id()));
+        let store = FsPageStore::new(&dir);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=60"),
+        );
+        headers.insert(HASHIRA_CACHE_TAGS_HEADER, HeaderValue::from_static("todos"));
+        headers.insert(header::ETAG, HeaderValue::from_static("\"1\""));
+
+        let page = StoredPage::new(Bytes::from_static(b"todos"), &headers);
+        store.put("/todos", PageArtifact::Html, page).unwrap();
+
+        let page = store.get("/todos", PageArtifact::Html).unwrap().unwrap();
+        let res = page.to_response(PageArtifact::Html);
+        assert_eq!(res.headers()[header::CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(res.headers()[HASHIRA_CACHE_TAGS_HEADER], "todos");
+        assert_eq!(
+            res.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(res.headers().get(header::ETAG).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn in_memory_store_test() {
+        let store = InMemoryPageStore::new(2);
+
+        store.put("/a", PageArtifact::Html, page(b"a")).unwrap();
+        store.put("/b", PageArtifact::Html, page(b"b")).unwrap();
+
+        // `/a` is now the most recently used
+        assert!(store.get("/a", PageArtifact::Html).unwrap().is_some());
+
+        store.put("/c", PageArtifact::Html, page(b"c")).unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.get("/a", PageArtifact::Html).unwrap().is_some());
+        assert!(store.get("/b", PageArtifact::Html).unwrap().is_none());
+        assert!(store.get("/c", PageArtifact::Html).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn store_skips_private_responses_test() {
+        let regeneration = StaticRegeneration::in_memory(10);
+
+        let mut res = Response::new(Body::from("private"));
+        res.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static("private, max-age=60"),
+        );
+        regeneration.store("/a", PageArtifact::Html, res).await;
+
+        let mut res = Response::new(Body::from("session"));
+        res.headers_mut()
+            .insert(header::SET_COOKIE, HeaderValue::from_static("session=1"));
+        regeneration.store("/b", PageArtifact::Html, res).await;
+
+        let res = Response::new(Body::from("public"));
+        regeneration.store("/c", PageArtifact::Html, res).await;
+
+        assert!(regeneration.get("/a", PageArtifact::Html).await.is_none());
+        assert!(regeneration.get("/b", PageArtifact::Html).await.is_none());
+        assert!(regeneration.get("/c", PageArtifact::Html).await.is_some());
+    }
+
+    #[test]
+    fn page_request_test() {
+        let req = Request::builder()
+            .uri("/search?q=rust")
+            .header(header::COOKIE, "session=1")
+            .header(header::HOST, "example.com")
+            .body(())
+            .unwrap();
+
+        let page_req = PageArtifact::Html.page_request(&req);
+        assert_eq!(page_req.uri(), "/search");
+        assert!(page_req.headers().get(header::COOKIE).is_none());
+        assert_eq!(page_req.headers()[header::HOST], "example.com");
+    }
+}
@@ -1,2 +1,5 @@
 #[cfg(target_arch = "wasm32")]
-pub mod wasm;
\ No newline at end of file
+pub mod wasm;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) mod page_cache;
@@ -0,0 +1,86 @@
+use crate::{app::CachePolicy, components::PageData};
+use std::{cell::RefCell, collections::HashMap};
+
+struct CacheEntry {
+    page_data: PageData,
+    policy: CachePolicy,
+    fetched_at: f64,
+}
+
+/// A page data stored in the client cache.
+pub(crate) enum CachedPageData {
+    /// The page data can be used.
+    Fresh(PageData),
+
+    /// The page data can be used but should be revalidated.
+    Stale(PageData),
+}
+
+thread_local! {
+    // The page data fetched by the client router by url
+    static PAGE_CACHE: RefCell<HashMap<String, CacheEntry>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the cached page data for the given url, if is not expired.
+pub(crate) fn get(url: &str) -> Option<CachedPageData> {
+    let now = js_sys::Date::now();
+
+    PAGE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.get(url)?;
+
+        let age = (now - entry.fetched_at) / 1000.0;
+        let max_age = entry.policy.get_max_age().unwrap_or_default().as_secs_f64();
+        let swr = entry
+            .policy
+            .get_stale_while_revalidate()
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        if age < max_age {
+            Some(CachedPageData::Fresh(entry.page_data.clone()))
+        } else if age < max_age + swr {
+            Some(CachedPageData::Stale(entry.page_data.clone()))
+        } else {
+            cache.remove(url);
+            None
+        }
+    })
+}
+
+/// Stores the page data of the given url if the policy allow it.
+pub(crate) fn insert(url: String, page_data: PageData, policy: CachePolicy) {
+    if !policy.is_cacheable() {
+        return;
+    }
+
+    let entry = CacheEntry {
+        page_data,
+        policy,
+        fetched_at: js_sys::Date::now(),
+    };
+
+    PAGE_CACHE.with(|cache| {
+        cache.borrow_mut().insert(url, entry);
+    });
+}
+
+/// Removes the cached page data of the given url.
+pub(crate) fn remove(url: &str) {
+    PAGE_CACHE.with(|cache| {
+        cache.borrow_mut().remove(url);
+    });
+}
+
+/// Removes all the cached page data that have any of the given tags.
+pub(crate) fn invalidate_tags(tags: &[&str]) {
+    PAGE_CACHE.with(|cache| {
+        cache.borrow_mut().retain(|_, entry| {
+            !entry
+                .policy
+                .tags()
+                .iter()
+                .any(|t| tags.contains(&t.as_str()))
+        });
+    });
+}
@@ -13,10 +13,307 @@ pub async fn fetch_json<S: serde::de::DeserializeOwned>(
 
     let resp: web_sys::Response = resp_value.dyn_into().unwrap();
 
+    read_json_response(resp).await
+}
+
+/// The reason a request was not completed.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub(crate) enum SendError {
+    /// The request was aborted.
+    Cancelled,
+
+    /// The request took longer than the timeout.
+    TimedOut,
+
+    /// The request failed due a network error.
+    Network,
+
+    /// The request could not be created.
+    Other(crate::error::BoxError),
+}
+
+#[cfg(target_arch = "wasm32")]
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Cancelled => write!(f, "the request was cancelled"),
+            SendError::TimedOut => write!(f, "the request timed out"),
+            SendError::Network => write!(f, "failed to send the request"),
+            SendError::Other(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl std::error::Error for SendError {}
+
+/// Sends a request using `fetch` and returns the response.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn send_with_fetch(
+    request: &web_sys::Request,
+    signal: Option<&web_sys::AbortSignal>,
+    timeout: Option<std::time::Duration>,
+) -> Result<web_sys::Response, SendError> {
+    use crate::error::JsError;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    fn other(err: JsValue) -> SendError {
+        SendError::Other(JsError::new(err).into())
+    }
+
+    if signal.map(|x| x.aborted()).unwrap_or(false) {
+        return Err(SendError::Cancelled);
+    }
+
+    // Each request has its own controller, so the timeout only aborts this request
+    let window = web_sys::window().unwrap();
+    let controller = web_sys::AbortController::new().map_err(other)?;
+
+    let on_abort = {
+        let controller = controller.clone();
+        Closure::<dyn Fn()>::new(move || controller.abort())
+    };
+
+    if let Some(signal) = signal {
+        signal
+            .add_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref())
+            .map_err(other)?;
+    }
+
+    let timed_out = Rc::new(Cell::new(false));
+    let on_timeout = {
+        let controller = controller.clone();
+        let timed_out = timed_out.clone();
+        Closure::<dyn Fn()>::new(move || {
+            timed_out.set(true);
+            controller.abort();
+        })
+    };
+
+    let timeout_id = match timeout {
+        Some(timeout) => Some(
+            window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    on_timeout.as_ref().unchecked_ref(),
+                    timeout.as_millis() as i32,
+                )
+                .map_err(other)?,
+        ),
+        None => None,
+    };
+
+    let mut init = web_sys::RequestInit::new();
+    init.signal(Some(&controller.signal()));
+
+    let ret = JsFuture::from(window.fetch_with_request_and_init(request, &init)).await;
+
+    // The closures are dropped after this function returns
+    if let Some(timeout_id) = timeout_id {
+        window.clear_timeout_with_handle(timeout_id);
+    }
+
+    if let Some(signal) = signal {
+        let _ =
+            signal.remove_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref());
+    }
+
+    match ret {
+        Ok(resp) => Ok(resp.unchecked_into()),
+        Err(_) if timed_out.get() => Err(SendError::TimedOut),
+        Err(_) if controller.signal().aborted() => Err(SendError::Cancelled),
+        Err(_) => Err(SendError::Network),
+    }
+}
+
+/// Sends a request and returns the response, reporting the progress of the upload and download.
+///
+/// The request is sent using a `XMLHttpRequest` because `fetch` doesn't report the upload progress.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn send_with_progress<U, D>(
+    request: &web_sys::Request,
+    signal: Option<&web_sys::AbortSignal>,
+    timeout: Option<std::time::Duration>,
+    on_upload: U,
+    on_download: D,
+) -> Result<web_sys::Response, SendError>
+where
+    U: Fn(crate::actions::Progress) + 'static,
+    D: Fn(crate::actions::Progress) + 'static,
+{
+    use crate::{actions::Progress, error::JsError};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{ProgressEvent, XmlHttpRequest};
+
+    fn get_progress(event: ProgressEvent) -> Progress {
+        Progress {
+            loaded: event.loaded() as u64,
+            total: event.length_computable().then(|| event.total() as u64),
+        }
+    }
+
+    fn other(err: JsValue) -> SendError {
+        SendError::Other(JsError::new(err).into())
+    }
+
+    if signal.map(|x| x.aborted()).unwrap_or(false) {
+        return Err(SendError::Cancelled);
+    }
+
+    let xhr = XmlHttpRequest::new().map_err(other)?;
+    xhr.open_with_async(&request.method(), &request.url(), true)
+        .map_err(other)?;
+
+    if let Some(timeout) = timeout {
+        xhr.set_timeout(timeout.as_millis() as u32);
+    }
+
+    // Copy the headers, this includes the boundary of a multipart form
+    if let Some(entries) = js_sys::try_iter(&request.headers()).map_err(other)? {
+        for entry in entries {
+            let entry: js_sys::Array = entry.map_err(other)?.unchecked_into();
+            let name = entry.get(0).as_string().unwrap_or_default();
+            let value = entry.get(1).as_string().unwrap_or_default();
+            xhr.set_request_header(&name, &value).map_err(other)?;
+        }
+    }
+
+    let body = match request.body() {
+        Some(_) => {
+            let blob = request.blob().map_err(other)?;
+            let blob = JsFuture::from(blob).await.map_err(other)?;
+            Some(blob.unchecked_into::<web_sys::Blob>())
+        }
+        None => None,
+    };
+
+    let on_upload = Closure::<dyn Fn(ProgressEvent)>::new(move |e| on_upload(get_progress(e)));
+    let on_download = Closure::<dyn Fn(ProgressEvent)>::new(move |e| on_download(get_progress(e)));
+    xhr.upload()
+        .map_err(other)?
+        .set_onprogress(Some(on_upload.as_ref().unchecked_ref()));
+    xhr.set_onprogress(Some(on_download.as_ref().unchecked_ref()));
+
+    let on_abort = {
+        let xhr = xhr.clone();
+        Closure::<dyn Fn()>::new(move || {
+            let _ = xhr.abort();
+        })
+    };
+
+    if let Some(signal) = signal {
+        signal
+            .add_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref())
+            .map_err(other)?;
+    }
+
+    // Each event rejects with its name
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+        let reject_with = |reason: &str| -> js_sys::Function {
+            reject
+                .bind1(&JsValue::NULL, &JsValue::from(reason))
+                .unchecked_into()
+        };
+
+        xhr.set_onload(Some(&resolve));
+        xhr.set_onerror(Some(&reject_with("error")));
+        xhr.set_ontimeout(Some(&reject_with("timeout")));
+        xhr.set_onabort(Some(&reject_with("abort")));
+    });
+
+    xhr.send_with_opt_blob(body.as_ref()).map_err(other)?;
+
+    let ret = JsFuture::from(done).await;
+
+    // The closures are dropped after this function returns
+    if let Some(signal) = signal {
+        let _ =
+            signal.remove_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref());
+    }
+
+    xhr.set_onprogress(None);
+    if let Ok(upload) = xhr.upload() {
+        upload.set_onprogress(None);
+    }
+
+    if let Err(reason) = ret {
+        return match reason.as_string().as_deref() {
+            Some("timeout") => Err(SendError::TimedOut),
+            Some("abort") => Err(SendError::Cancelled),
+            _ => Err(SendError::Network),
+        };
+    }
+
+    // Convert the result to a `Response` to read it as any other response
+    let headers = web_sys::Headers::new().map_err(other)?;
+    let raw_headers = xhr.get_all_response_headers().map_err(other)?;
+    for line in raw_headers.split("\r\n") {
+        if let Some((name, value)) = line.split_once(':') {
+            let _ = headers.append(name.trim(), value.trim());
+        }
+    }
+
+    let mut init = web_sys::ResponseInit::new();
+    init.status(xhr.status().map_err(other)?);
+    init.headers(&headers);
+
+    let text = xhr.response_text().map_err(other)?.unwrap_or_default();
+    let body = (!text.is_empty()).then_some(text.as_str());
+    web_sys::Response::new_with_opt_str_and_init(body, &init).map_err(other)
+}
+
+/// Waits the given duration.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            );
+    });
+
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Reads the json of a successful response, or returns the error sent by the server.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn read_json_response<S: serde::de::DeserializeOwned>(
+    resp: web_sys::Response,
+) -> Result<S, crate::error::BoxError> {
+    use crate::error::JsError;
+    use wasm_bindgen_futures::JsFuture;
+
     if !resp.ok() {
         return Err(get_response_error(resp).await);
     }
 
+    invalidate_page_cache(&resp);
+
+    // A streamed response is received as an array of its values
+    let content_type = resp.headers().get("content-type").ok().flatten();
+    let essence = content_type
+        .as_deref()
+        .and_then(|x| x.split(';').next())
+        .map(str::trim);
+
+    if essence == Some(crate::web::APPLICATION_NDJSON) {
+        let text = resp.text().map_err(JsError::new)?;
+        let text = JsFuture::from(text).await.map_err(JsError::new)?;
+        let text = text.as_string().unwrap_or_default();
+        let values = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        return Ok(serde_json::from_str(&format!("[{values}]"))?);
+    }
+
     // Convert this other `Promise` into a rust `Future`.
     let json = resp.json().map_err(JsError::new)?;
     let json = JsFuture::from(json).await.map_err(JsError::new)?;
@@ -30,6 +327,96 @@ pub async fn fetch_json<S: serde::de::DeserializeOwned>(
     }
 }
 
+/// Fetch a stream of newline delimited json and calls the given function with each value.
+///
+/// The stream is cancelled if the function returns `false` or the signal is aborted.
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_json_stream<S, F>(
+    request: web_sys::Request,
+    signal: Option<&web_sys::AbortSignal>,
+    mut on_value: F,
+) -> Result<(), crate::error::BoxError>
+where
+    S: serde::de::DeserializeOwned,
+    F: FnMut(S) -> bool,
+{
+    use crate::error::JsError;
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    let mut init = web_sys::RequestInit::new();
+    init.signal(signal);
+
+    let window = web_sys::window().unwrap();
+    let resp_value = JsFuture::from(window.fetch_with_request_and_init(&request, &init))
+        .await
+        .map_err(JsError::new)?;
+
+    let resp: web_sys::Response = resp_value.dyn_into().unwrap();
+
+    if !resp.ok() {
+        return Err(get_response_error(resp).await);
+    }
+
+    invalidate_page_cache(&resp);
+
+    let Some(body) = resp.body() else {
+        return Ok(());
+    };
+
+    let reader = body
+        .get_reader()
+        .unchecked_into::<web_sys::ReadableStreamDefaultReader>();
+    let mut buf = Vec::new();
+
+    loop {
+        let chunk = JsFuture::from(reader.read()).await.map_err(JsError::new)?;
+        let done = js_sys::Reflect::get(&chunk, &JsValue::from("done"))
+            .map_err(JsError::new)?
+            .as_bool()
+            .unwrap_or(true);
+
+        if done {
+            break;
+        }
+
+        let value = js_sys::Reflect::get(&chunk, &JsValue::from("value")).map_err(JsError::new)?;
+        buf.extend(js_sys::Uint8Array::new(&value).to_vec());
+
+        // Each line is a value
+        while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let line = buf.drain(..=pos).collect::<Vec<_>>();
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            if !on_value(serde_json::from_slice(&line)?) {
+                let _ = reader.cancel();
+                return Ok(());
+            }
+        }
+    }
+
+    // The last value may not end with a new line
+    if !buf.iter().all(u8::is_ascii_whitespace) {
+        on_value(serde_json::from_slice(&buf)?);
+    }
+
+    Ok(())
+}
+
+// The response may invalidate the page data cached by the client router
+#[cfg(target_arch = "wasm32")]
+fn invalidate_page_cache(resp: &web_sys::Response) {
+    if let Ok(Some(tags)) = resp
+        .headers()
+        .get(crate::components::HASHIRA_INVALIDATE_TAGS_HEADER)
+    {
+        let tags = tags.split(',').map(str::trim).collect::<Vec<_>>();
+        super::page_cache::invalidate_tags(&tags);
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 async fn get_response_error(resp: web_sys::Response) -> crate::error::BoxError {
     use wasm_bindgen_futures::JsFuture;
@@ -42,18 +429,187 @@ async fn get_response_error(resp: web_sys::Response) -> crate::error::BoxError {
         .flatten()
         .unwrap_or_default();
 
-    let error_message = match content_type.as_str() {
-        "application/json" => {
-            let json = resp.json().unwrap();
-            let json = JsFuture::from(json).await.unwrap();
-            serde_wasm_bindgen::from_value::<String>(json).unwrap()
+    let text = resp.text().unwrap();
+    let text = JsFuture::from(text).await.unwrap();
+    let text = text.as_string().unwrap_or_default();
+
+    if content_type.starts_with("application/json") {
+        // The errors of the fields of the input
+        if let Some(errors) = crate::actions::ValidationErrors::from_json(&text) {
+            return errors.into();
         }
-        _ => {
-            let text = resp.text().unwrap();
-            let text = JsFuture::from(text).await.unwrap();
-            text.as_string().unwrap()
+
+        if let Ok(message) = serde_json::from_str::<String>(&text) {
+            return message.into();
         }
-    };
+    }
 
-    error_message.into()
+    text.into()
+}
+
+/// Fetch the `PageData` of the page at the given url and its cache policy.
+///
+/// Returns `None` if the server did not respond with the page data,
+/// for example if the url is not a page.
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_page_data(
+    url: &str,
+) -> Result<
+    Option<(crate::components::PageData, Option<crate::app::CachePolicy>)>,
+    crate::error::BoxError,
+> {
+    use crate::components::HASHIRA_PAGE_DATA_HEADER;
+    use crate::error::JsError;
+    use web_sys::{Headers, RequestInit};
+
+    let headers = Headers::new().map_err(JsError::new)?;
+    headers
+        .set(HASHIRA_PAGE_DATA_HEADER, "true")
+        .map_err(JsError::new)?;
+
+    let mut init = RequestInit::new();
+    init.headers(&headers);
+
+    let request = web_sys::Request::new_with_str_and_init(url, &init).map_err(JsError::new)?;
+    let resp = fetch(&request).await?;
+
+    // A static file server ignores the page data header and responds with the exported html,
+    // in that case we request the page data file exported next to the html
+    if resp.ok() && get_content_type(&resp).starts_with("text/html") {
+        let page_data_url = get_exported_page_data_url(url)?;
+        let request = web_sys::Request::new_with_str(&page_data_url).map_err(JsError::new)?;
+        let resp = fetch(&request).await?;
+        return read_page_data(resp).await;
+    }
+
+    read_page_data(resp).await
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch(request: &web_sys::Request) -> Result<web_sys::Response, crate::error::BoxError> {
+    use crate::error::JsError;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let window = web_sys::window().unwrap();
+    let resp_value = JsFuture::from(window.fetch_with_request(request))
+        .await
+        .map_err(JsError::new)?;
+
+    Ok(resp_value.dyn_into().unwrap())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn get_content_type(resp: &web_sys::Response) -> String {
+    resp.headers()
+        .get("content-type")
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Returns the url of the page data file of an exported page.
+#[cfg(target_arch = "wasm32")]
+fn get_exported_page_data_url(url: &str) -> Result<String, crate::error::BoxError> {
+    use crate::components::HASHIRA_PAGE_DATA_FILE;
+    use crate::error::JsError;
+
+    let base = web_sys::window()
+        .unwrap()
+        .location()
+        .href()
+        .map_err(JsError::new)?;
+
+    let url = web_sys::Url::new_with_base(url, &base).map_err(JsError::new)?;
+    let path = url.pathname();
+    url.set_pathname(&format!(
+        "{}/{HASHIRA_PAGE_DATA_FILE}",
+        path.trim_end_matches('/')
+    ));
+    url.set_search("");
+    Ok(url.href())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn read_page_data(
+    resp: web_sys::Response,
+) -> Result<
+    Option<(crate::components::PageData, Option<crate::app::CachePolicy>)>,
+    crate::error::BoxError,
+> {
+    use crate::app::CachePolicy;
+    use crate::components::HASHIRA_CACHE_TAGS_HEADER;
+    use crate::error::JsError;
+    use wasm_bindgen_futures::JsFuture;
+
+    // Error pages also respond with the page data, so we only check the content type
+    if !get_content_type(&resp).starts_with("application/json") {
+        return Ok(None);
+    }
+
+    let cache_policy = resp
+        .headers()
+        .get("cache-control")
+        .ok()
+        .flatten()
+        .and_then(|cache_control| {
+            let tags = resp.headers().get(HASHIRA_CACHE_TAGS_HEADER).ok().flatten();
+            CachePolicy::parse(&cache_control, tags.as_deref())
+        });
+
+    let text = resp.text().map_err(JsError::new)?;
+    let text = JsFuture::from(text).await.map_err(JsError::new)?;
+    let text = text.as_string().unwrap_or_default();
+    let page_data = serde_json::from_str(&text)?;
+    Ok(Some((page_data, cache_policy)))
+}
+
+/// Returns the deferred value with the given id streamed by the server, if was already received.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn read_deferred_value(id: usize) -> Option<serde_json::Value> {
+    let document = web_sys::window()?.document()?;
+    let element_id = crate::components::deferred_element_id(id);
+    let json = document.get_element_by_id(&element_id)?.text_content()?;
+
+    match serde_json::from_str(&json) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::error!("failed to parse deferred value `{id}`: {err}");
+            None
+        }
+    }
+}
+
+/// Waits until the server streams the deferred value with the given id.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn wait_deferred_value(id: usize) -> serde_json::Value {
+    use crate::components::HASHIRA_DEFERRED_EVENT;
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let window = web_sys::window().unwrap();
+
+    loop {
+        if let Some(value) = read_deferred_value(id) {
+            return value;
+        }
+
+        // Wait for the next deferred value
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        let mut tx = Some(tx);
+        let callback = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            if let Some(tx) = tx.take() {
+                let _ = tx.send(());
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+
+        window
+            .add_event_listener_with_callback(HASHIRA_DEFERRED_EVENT, callback.as_ref().unchecked_ref())
+            .expect("failed to add event listener");
+
+        let _ = rx.await;
+        let _ = window.remove_event_listener_with_callback(
+            HASHIRA_DEFERRED_EVENT,
+            callback.as_ref().unchecked_ref(),
+        );
+    }
 }
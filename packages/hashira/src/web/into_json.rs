@@ -15,7 +15,7 @@ use std::{
     },
 };
 
-use super::{Json, Response};
+use super::{Body, Json, Response};
 use crate::error::{BoxError, ServerError};
 use http::{header, response::Parts, HeaderMap, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
@@ -27,6 +27,19 @@ pub trait IntoJsonResponse {
 
     /// Converts this type info a json response.
     fn into_json_response(self) -> crate::Result<Response<Self::Data>>;
+
+    /// Converts this type into a response with the data serialized as json.
+    ///
+    /// Types that send the data in parts, like `JsonStream`, stream the response instead,
+    /// so the types wrapping other `IntoJsonResponse` must forward this method.
+    fn into_serialized_response(self) -> crate::Result<Response>
+    where
+        Self: Sized,
+    {
+        let (parts, data) = self.into_json_response()?.into_parts();
+        let bytes = serde_json::to_vec(&data)?;
+        Ok(Response::from_parts(parts, Body::from(bytes)))
+    }
 }
 
 impl IntoJsonResponse for () {
@@ -87,6 +100,13 @@ where
             None => Err(ServerError::from(StatusCode::NOT_FOUND).into()),
         }
     }
+
+    fn into_serialized_response(self) -> crate::Result<Response> {
+        match self {
+            Some(x) => x.into_serialized_response(),
+            None => Err(ServerError::from(StatusCode::NOT_FOUND).into()),
+        }
+    }
 }
 
 impl<T, E> IntoJsonResponse for Result<T, E>
@@ -102,6 +122,13 @@ where
             Err(err) => Err(err.into()),
         }
     }
+
+    fn into_serialized_response(self) -> crate::Result<Response> {
+        match self {
+            Ok(x) => x.into_serialized_response(),
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 impl IntoJsonResponse for serde_json::Value {
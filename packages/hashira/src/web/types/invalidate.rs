@@ -0,0 +1,119 @@
+use crate::{
+    app::{is_valid_cache_tag, tags_header_value},
+    components::HASHIRA_INVALIDATE_TAGS_HEADER,
+    web::{IntoJsonResponse, IntoResponse, Response},
+};
+
+/// Wraps a response and invalidates the cached pages with the given tags.
+///
+/// The client drops all the cached page data with any of the tags when receives the response,
+/// this is useful to return from server actions that modify the data displayed by a page.
+///
+/// # Example
+/// ```rs,no_run
+/// #[action]
+/// async fn CreateTodoAction(form: Form<NewTodo>) -> hashira::Result<InvalidateTags<Json<Todo>>> {
+///     let todo = create_todo(form.into_inner()).await?;
+///     Ok(InvalidateTags::new(Json(todo)).tag("todos"))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InvalidateTags<T> {
+    inner: T,
+    tags: Vec<String>,
+}
+
+impl<T> InvalidateTags<T> {
+    /// Constructs a response that invalidates no tags.
+    pub fn new(inner: T) -> Self {
+        InvalidateTags {
+            inner,
+            tags: vec![],
+        }
+    }
+
+    /// Adds a tag to invalidate.
+    ///
+    /// Tags must be non-empty visible ASCII without commas, invalid tags are ignored.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if is_valid_cache_tag(&tag) {
+            self.tags.push(tag);
+        } else {
+            log::warn!("ignoring invalid cache tag: {tag:?}");
+        }
+
+        self
+    }
+
+    /// Returns the tags to invalidate.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn insert_header<B>(tags: &[String], res: &mut Response<B>) {
+        if let Some(value) = tags_header_value(tags) {
+            res.headers_mut()
+                .insert(HASHIRA_INVALIDATE_TAGS_HEADER, value);
+        }
+    }
+}
+
+impl<T> IntoResponse for InvalidateTags<T>
+where
+    T: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut res = self.inner.into_response();
+        Self::insert_header(&self.tags, &mut res);
+        res
+    }
+}
+
+impl<T> IntoJsonResponse for InvalidateTags<T>
+where
+    T: IntoJsonResponse,
+{
+    type Data = T::Data;
+
+    fn into_json_response(self) -> crate::Result<Response<Self::Data>> {
+        let mut res = self.inner.into_json_response()?;
+        Self::insert_header(&self.tags, &mut res);
+        Ok(res)
+    }
+
+    fn into_serialized_response(self) -> crate::Result<Response> {
+        let mut res = self.inner.into_serialized_response()?;
+        Self::insert_header(&self.tags, &mut res);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InvalidateTags;
+    use crate::{
+        components::HASHIRA_INVALIDATE_TAGS_HEADER,
+        web::{IntoJsonResponse, Json},
+    };
+
+    #[test]
+    fn invalidate_tags_header_test() {
+        let res = InvalidateTags::new(Json(10))
+            .tag("todos")
+            .tag("users")
+            .into_json_response()
+            .unwrap();
+
+        assert_eq!(
+            res.headers().get(HASHIRA_INVALIDATE_TAGS_HEADER).unwrap(),
+            "todos,users"
+        );
+        assert_eq!(*res.body(), 10);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    error::BoxError,
+    types::TryBoxStream,
+    web::{Body, IntoJsonResponse, IntoResponse, InvalidateTags, Response},
+};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::{header, HeaderValue};
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+
+/// Mime type of the newline delimited json.
+pub(crate) const APPLICATION_NDJSON: &str = "application/x-ndjson";
+
+/// A stream of json values sent as newline delimited json.
+///
+/// Actions can return a `JsonStream` to send each value as soon is ready,
+/// use the `use_action_stream` hook to receive the values on the client,
+/// `use_action` and `fetch_action` receive all the values once the stream ends.
+/// If the stream fails the response ends before the rest of the values are sent.
+///
+/// # Example
+/// ```rust,ignore
+/// #[action]
+/// async fn ExportTodos(Inject(pool): Inject<DbPool>) -> JsonStream<Todo> {
+///     JsonStream::new(get_todos_stream(pool))
+/// }
+/// ```
+pub struct JsonStream<T> {
+    stream: TryBoxStream<Bytes>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonStream<T>
+where
+    T: Serialize + 'static,
+{
+    /// Constructs a json stream from a stream of values.
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + Send + Sync + 'static,
+    {
+        Self::try_new(stream.map(Ok::<_, BoxError>))
+    }
+
+    /// Constructs a json stream from a stream of results, the response ends on the first error.
+    pub fn try_new<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + Sync + 'static,
+        E: Into<BoxError>,
+    {
+        let stream = stream.map(|ret| {
+            let value = ret.map_err(Into::into)?;
+            let mut line = serde_json::to_vec(&value)?;
+            line.push(b'\n');
+            Ok(Bytes::from(line))
+        });
+
+        JsonStream {
+            stream: Box::pin(stream),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> IntoResponse for JsonStream<T> {
+    fn into_response(self) -> Response {
+        let mut res = Response::new(Body::from(self.stream));
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(APPLICATION_NDJSON),
+        );
+        res
+    }
+}
+
+impl<T> IntoJsonResponse for JsonStream<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    // When not streamed, the client receives all the values
+    type Data = Vec<T>;
+
+    fn into_json_response(self) -> crate::Result<Response<Self::Data>> {
+        Err("json streams can only be sent as a streamed response".into())
+    }
+
+    fn into_serialized_response(self) -> crate::Result<Response> {
+        Ok(self.into_response())
+    }
+}
+
+/// A json response that is sent as a stream of values.
+pub trait IntoJsonStream: IntoJsonResponse<Data = Vec<Self::Item>> {
+    /// The type of each value of the stream.
+    type Item: Serialize + DeserializeOwned;
+}
+
+impl<T> IntoJsonStream for JsonStream<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Item = T;
+}
+
+impl<S, E> IntoJsonStream for Result<S, E>
+where
+    S: IntoJsonStream,
+    E: Into<BoxError>,
+{
+    type Item = S::Item;
+}
+
+impl<S> IntoJsonStream for Option<S>
+where
+    S: IntoJsonStream,
+{
+    type Item = S::Item;
+}
+
+impl<S> IntoJsonStream for InvalidateTags<S>
+where
+    S: IntoJsonStream,
+{
+    type Item = S::Item;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonStream;
+    use crate::{
+        components::HASHIRA_INVALIDATE_TAGS_HEADER,
+        error::ServerError,
+        web::{IntoJsonResponse, InvalidateTags, ResponseExt},
+    };
+    use http::StatusCode;
+
+    #[tokio::test]
+    async fn json_stream_test() {
+        let stream = JsonStream::new(futures::stream::iter(vec![1, 2, 3]));
+        let res = Ok::<_, crate::error::BoxError>(stream)
+            .into_serialized_response()
+            .unwrap();
+
+        assert_eq!(
+            res.content_type().unwrap().essence_str(),
+            "application/x-ndjson"
+        );
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "1\n2\n3\n");
+
+        let failing = JsonStream::try_new(futures::stream::iter(vec![
+            Ok(1),
+            Err(std::io::Error::other("failed")),
+        ]));
+
+        let body = failing.into_serialized_response().unwrap().into_body();
+        assert!(body.into_bytes().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn json_stream_wrappers_test() {
+        let stream = JsonStream::new(futures::stream::iter(vec![1, 2]));
+        let res = Some(InvalidateTags::new(stream).tag("todos"))
+            .into_serialized_response()
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(HASHIRA_INVALIDATE_TAGS_HEADER).unwrap(),
+            "todos"
+        );
+        assert_eq!(
+            res.content_type().unwrap().essence_str(),
+            "application/x-ndjson"
+        );
+
+        let bytes = res.into_body().into_bytes().await.unwrap();
+        assert_eq!(bytes, "1\n2\n");
+
+        let err = None::<JsonStream<i32>>
+            .into_serialized_response()
+            .unwrap_err();
+        let err = err.downcast::<ServerError>().unwrap();
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+    }
+}
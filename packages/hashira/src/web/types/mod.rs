@@ -10,9 +10,15 @@ pub use string::*;
 mod query;
 pub use query::*;
 
+mod path;
+pub use path::*;
+
 mod json;
 pub use json::*;
 
+mod json_stream;
+pub use json_stream::*;
+
 mod html;
 pub use html::*;
 
@@ -44,4 +50,10 @@ mod either_;
 pub use either_::*;
 
 mod addr;
-pub use addr::*;
\ No newline at end of file
+pub use addr::*;
+
+mod invalidate;
+pub use invalidate::*;
+
+mod valid;
+pub use valid::*;
@@ -0,0 +1,476 @@
+use crate::{
+    app::RequestContext,
+    error::ServerError,
+    web::{Body, FromRequest},
+};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+use std::{
+    future::{ready, Ready},
+    ops::Deref,
+};
+
+/// Extracts the params of the matched route.
+///
+/// The params can be extracted into a struct using its names, into a tuple using its position,
+/// or into a single value if the route only have one param. The params are percent-decoded
+/// before parsing, if a param cannot be parsed the request is rejected with a `400 Bad Request` naming the param.
+///
+/// # Example
+/// ```rust,ignore
+/// #[derive(Deserialize)]
+/// struct PostParams {
+///     user_id: u64,
+///     post_id: u64,
+/// }
+///
+/// // /users/:user_id/posts/:post_id
+/// async fn get_post(Path(params): Path<PostParams>) -> Json<Post> {
+///     todo!()
+/// }
+///
+/// // Or using a tuple
+/// async fn get_post(Path((user_id, post_id)): Path<(u64, u64)>) -> Json<Post> {
+///     todo!()
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path<T>(pub T);
+
+impl<T> Path<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Path<T> {
+    type Error = ServerError;
+    type Fut = Ready<Result<Path<T>, Self::Error>>;
+
+    fn from_request(ctx: &RequestContext, _body: &mut Body) -> Self::Fut {
+        let ret = T::deserialize(de::ParamsDeserializer::new(ctx.params()))
+            .map(Path)
+            .map_err(|err| ServerError::new(StatusCode::BAD_REQUEST, err.to_string()));
+
+        ready(ret)
+    }
+}
+
+mod de {
+    use crate::routing::{decode_param, Params};
+    use serde::{
+        de::{self, DeserializeSeed, Error as _, IntoDeserializer, MapAccess, SeqAccess, Visitor},
+        forward_to_deserialize_any,
+    };
+    use std::{borrow::Cow, fmt::Display};
+
+    /// An error that occurred while deserializing the params.
+    #[derive(Debug)]
+    pub struct PathError {
+        param: Option<String>,
+        message: String,
+    }
+
+    impl PathError {
+        fn with_param(mut self, param: &str) -> Self {
+            if self.param.is_none() {
+                self.param = Some(param.to_owned());
+            }
+
+            self
+        }
+    }
+
+    impl Display for PathError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match &self.param {
+                Some(param) => write!(f, "invalid path param `{param}`: {}", self.message),
+                None => write!(f, "invalid path params: {}", self.message),
+            }
+        }
+    }
+
+    impl std::error::Error for PathError {}
+
+    impl de::Error for PathError {
+        fn custom<T: Display>(msg: T) -> Self {
+            PathError {
+                param: None,
+                message: msg.to_string(),
+            }
+        }
+    }
+
+    /// Deserializes all the params of a route.
+    pub struct ParamsDeserializer<'de> {
+        params: &'de Params,
+    }
+
+    impl<'de> ParamsDeserializer<'de> {
+        pub fn new(params: &'de Params) -> Self {
+            ParamsDeserializer { params }
+        }
+
+        // Deserializes the only param of the route
+        fn single(&self) -> Result<ParamDeserializer<'de>, PathError> {
+            match self.params.get_index(0) {
+                Some((key, value)) if self.params.len() == 1 => ParamDeserializer::new(key, value),
+                _ => Err(de::Error::custom(format!(
+                    "expected 1 param but found {}",
+                    self.params.len()
+                ))),
+            }
+        }
+    }
+
+    macro_rules! forward_to_single {
+        ($($method:ident)*) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                    let param = self.single()?;
+                    let key = param.key;
+                    param.$method(visitor).map_err(|err| err.with_param(key))
+                }
+            )*
+        };
+    }
+
+    impl<'de> de::Deserializer<'de> for ParamsDeserializer<'de> {
+        type Error = PathError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            self.deserialize_map(visitor)
+        }
+
+        fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_map(ParamsAccess {
+                iter: self.params.iter(),
+                current: None,
+            })
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.deserialize_map(visitor)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_seq(ParamsAccess {
+                iter: self.params.iter(),
+                current: None,
+            })
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            if self.params.len() != len {
+                return Err(de::Error::custom(format!(
+                    "expected {len} params but found {}",
+                    self.params.len()
+                )));
+            }
+
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.deserialize_tuple(len, visitor)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            let param = self.single()?;
+            let key = param.key;
+            param
+                .deserialize_enum(name, variants, visitor)
+                .map_err(|err| err.with_param(key))
+        }
+
+        forward_to_single! {
+            deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+            deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+            deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_option
+        }
+
+        forward_to_deserialize_any! {
+            i128 u128 bytes byte_buf unit unit_struct identifier ignored_any
+        }
+    }
+
+    struct ParamsAccess<'de> {
+        iter: indexmap::map::Iter<'de, String, String>,
+        current: Option<(&'de str, &'de str)>,
+    }
+
+    impl<'de> MapAccess<'de> for ParamsAccess<'de> {
+        type Error = PathError;
+
+        fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+        where
+            K: DeserializeSeed<'de>,
+        {
+            match self.iter.next() {
+                Some((key, value)) => {
+                    self.current = Some((key, value));
+                    seed.deserialize(key.as_str().into_deserializer()).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+        where
+            V: DeserializeSeed<'de>,
+        {
+            let (key, value) = self
+                .current
+                .take()
+                .ok_or_else(|| de::Error::custom("value is missing"))?;
+
+            seed.deserialize(ParamDeserializer::new(key, value)?)
+                .map_err(|err| err.with_param(key))
+        }
+    }
+
+    impl<'de> SeqAccess<'de> for ParamsAccess<'de> {
+        type Error = PathError;
+
+        fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+        where
+            T: DeserializeSeed<'de>,
+        {
+            match self.iter.next() {
+                Some((key, value)) => seed
+                    .deserialize(ParamDeserializer::new(key, value)?)
+                    .map(Some)
+                    .map_err(|err| err.with_param(key)),
+                None => Ok(None),
+            }
+        }
+    }
+
+    /// Deserializes the value of a single param.
+    struct ParamDeserializer<'de> {
+        key: &'de str,
+        value: Cow<'de, str>,
+    }
+
+    impl<'de> ParamDeserializer<'de> {
+        // The values are percent-encoded in the path
+        fn new(key: &'de str, value: &'de str) -> Result<Self, PathError> {
+            match decode_param(value) {
+                Some(value) => Ok(ParamDeserializer { key, value }),
+                None => Err(PathError::custom("invalid percent-encoded value").with_param(key)),
+            }
+        }
+    }
+
+    macro_rules! parse_value {
+        ($($method:ident => $visit:ident,)*) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                    match self.value.parse() {
+                        Ok(value) => visitor.$visit(value),
+                        Err(err) => Err(PathError::custom(err).with_param(self.key)),
+                    }
+                }
+            )*
+        };
+    }
+
+    impl<'de> de::Deserializer<'de> for ParamDeserializer<'de> {
+        type Error = PathError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self.value {
+                Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+                Cow::Owned(value) => visitor.visit_string(value),
+            }
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_enum(self.value.into_deserializer())
+        }
+
+        parse_value! {
+            deserialize_bool => visit_bool,
+            deserialize_i8 => visit_i8,
+            deserialize_i16 => visit_i16,
+            deserialize_i32 => visit_i32,
+            deserialize_i64 => visit_i64,
+            deserialize_u8 => visit_u8,
+            deserialize_u16 => visit_u16,
+            deserialize_u32 => visit_u32,
+            deserialize_u64 => visit_u64,
+            deserialize_f32 => visit_f32,
+            deserialize_f64 => visit_f64,
+            deserialize_char => visit_char,
+        }
+
+        forward_to_deserialize_any! {
+            i128 u128 str string bytes byte_buf unit unit_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{
+            router::{PageRouter, PageRouterWrapper},
+            AppData, RequestContext,
+        },
+        routing::{ErrorRouter, Params},
+        web::{Body, FromRequest, Path, Request},
+    };
+    use http::StatusCode;
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn path_from_request_test() {
+        #[derive(Deserialize)]
+        struct PostParams {
+            user_id: u64,
+            slug: String,
+        }
+
+        let ctx = create_request_context(&[("user_id", "12"), ("slug", "hello-world")]);
+        let Path(params) = Path::<PostParams>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(params.user_id, 12);
+        assert_eq!(params.slug, "hello-world");
+
+        let Path((user_id, slug)) = Path::<(u32, String)>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(user_id, 12);
+        assert_eq!(slug, "hello-world");
+
+        let ctx = create_request_context(&[("id", "7")]);
+        let Path(id) = Path::<u8>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(id, 7);
+    }
+
+    #[tokio::test]
+    async fn path_percent_decode_test() {
+        let ctx = create_request_context(&[("name", "a%20b%2Fc")]);
+        let Path(name) = Path::<String>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(name, "a b/c");
+
+        let ctx = create_request_context(&[("id", "%34%32")]);
+        let Path(id) = Path::<u64>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(id, 42);
+
+        let ctx = create_request_context(&[("name", "%FF")]);
+        let err = Path::<String>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn path_invalid_param_test() {
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        struct PostParams {
+            user_id: u64,
+            post_id: u64,
+        }
+
+        let ctx = create_request_context(&[("user_id", "12"), ("post_id", "first")]);
+        let err = Path::<PostParams>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert!(err.message().unwrap().contains("`post_id`"), "{err:?}");
+
+        let err = Path::<(u64, u64)>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap_err();
+
+        assert!(err.message().unwrap().contains("`post_id`"), "{err:?}");
+
+        let err = Path::<u64>::from_request(&ctx, &mut Body::empty())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    fn create_request_context(params: &[(&str, &str)]) -> RequestContext {
+        RequestContext::new(
+            Arc::new(Request::new(())),
+            Arc::new(AppData::default()),
+            PageRouterWrapper::from(PageRouter::new()),
+            Arc::new(ErrorRouter::new()),
+            None,
+            params.iter().copied().collect::<Params>(),
+        )
+    }
+}
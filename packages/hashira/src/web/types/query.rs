@@ -6,7 +6,7 @@ use serde::de::DeserializeOwned;
 use std::future::{ready, Ready};
 
 /// Represents an url search params.
-pub struct Query<Q>(Q);
+pub struct Query<Q>(pub(crate) Q);
 
 impl<Q> Query<Q> {
     /// Returns the inner query.
@@ -0,0 +1,84 @@
+use futures::Future;
+use pin_project_lite::pin_project;
+use std::{ops::Deref, task::Poll};
+
+use crate::{
+    actions::Validate,
+    app::RequestContext,
+    error::BoxError,
+    web::{Body, FromRequest},
+};
+
+/// Extracts a value and validates it.
+///
+/// If the value is invalid the request is rejected with a `422 Unprocessable Entity`
+/// containing the `ValidationErrors`.
+///
+/// # Example
+/// ```rust,ignore
+/// #[action]
+/// async fn CreateTodo(form: Valid<Form<NewTodo>>) -> Json<Todo> {
+///     todo!()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    /// Returns the validated value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for Valid<T>
+where
+    T: FromRequest + Validate,
+{
+    type Error = BoxError;
+    type Fut = FromRequestValidFuture<T::Fut>;
+
+    fn from_request(ctx: &RequestContext, body: &mut Body) -> Self::Fut {
+        FromRequestValidFuture {
+            fut: T::from_request(ctx, body),
+        }
+    }
+}
+
+pin_project! {
+    #[doc(hidden)]
+    pub struct FromRequestValidFuture<Fut> {
+        #[pin]
+        fut: Fut,
+    }
+}
+
+impl<Fut, T, E> Future for FromRequestValidFuture<Fut>
+where
+    Fut: Future<Output = Result<T, E>>,
+    T: Validate,
+    E: Into<BoxError>,
+{
+    type Output = Result<Valid<T>, BoxError>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let this = self.project();
+        let value = futures::ready!(this.fut.poll(cx)).map_err(Into::into)?;
+
+        match value.validate() {
+            Ok(_) => Poll::Ready(Ok(Valid(value))),
+            Err(errors) => Poll::Ready(Err(errors.into())),
+        }
+    }
+}